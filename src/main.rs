@@ -1,61 +1,255 @@
-use std::collections::HashMap;
-
-use card_and_enums::{Card, NumberEnum, SuitEnum};
+use alpha_beta::solve_two_player;
+use card_and_enums::Card;
+use game_match::Match;
 use game_state::{GameState, GameStateError};
+use max_n::{describe_move, search};
 use multi_counter::MultiCounter;
 use rand::{seq::SliceRandom, thread_rng};
+use rule_set::{DealingScheme, EndCondition, PlayRule, RuleSet, StalemateResolution};
+use search::{explore, explore_parallel, solve, TranspositionTable, Weighting};
 
 fn main() -> Result<(), String> {
-    let mut branches: Vec<GameState> = Vec::new();
-    let initial = GameState::new(4).map_err(|e| e.to_string())?;
-    let mut victories: Vec<u8> = Vec::new();
-
-    let mut game_state: Option<GameState> = None;
-    let mut next_game_state: Option<GameState> = None;
-    while victories.is_empty() || !branches.is_empty() {
-        match game_state {
-            Some(ref state) => match assess_decision(state.to_owned()) {
-                Ok(decision) => match decision {
-                    Decision::Victory(player) => victories.push(player),
-                    _ => next_game_state = Some(process_branches(&mut branches, decision)?),
-                },
-                Err(e) => return Err(e.to_string()),
-            },
-            None => match assess_decision(initial.to_owned()) {
-                Ok(decision) => match decision {
-                    Decision::Victory(player) => victories.push(player),
-                    _ => next_game_state = Some(process_branches(&mut branches, decision)?),
-                },
-                Err(e) => return Err(e.to_string()),
-            },
+    let options = cli::parse_args(std::env::args().skip(1)).map_err(|e| e.to_string())?;
+    if let Some(target_score) = options.match_target {
+        return play_match(options, target_score);
+    }
+    let dealer = options
+        .dealer
+        .unwrap_or(options.players.saturating_sub(1) as u8);
+
+    let initial =
+        GameState::new(options.players, options.rules, dealer).map_err(|e| e.to_string())?;
+    println!(
+        "Player {} deals, player {} leads",
+        initial.dealer + 1,
+        initial.player_turn + 1
+    );
+    let hand_sizes: Vec<usize> = initial
+        .players
+        .iter()
+        .map(|player| player.hand.len())
+        .collect();
+    let largest_hand = hand_sizes.iter().max().copied().unwrap_or(0);
+    if hand_sizes.iter().any(|size| *size != largest_hand) {
+        let extra_seats: Vec<String> = (0..hand_sizes.len())
+            .filter(|seat| hand_sizes[*seat] == largest_hand)
+            .map(|seat| (seat + 1).to_string())
+            .collect();
+        println!("Extra cards dealt to players {}", extra_seats.join(", "));
+    }
+    if !initial.widow.is_empty() {
+        println!("Widow of {} cards", initial.widow.len());
+    }
+    if !initial.stock.is_empty() {
+        println!("Stock of {} cards", initial.stock.len());
+    }
+    if options.alpha_beta {
+        let mut table = options
+            .table_megabytes
+            .map(|megabytes| TranspositionTable::new(megabytes * 1024 * 1024));
+        let solution =
+            solve_two_player(initial.clone(), table.as_mut()).map_err(|e| e.to_string())?;
+        // anything between a win and a loss depends on draws from the stock
+        let result = if solution.value == 1.0 {
+            "wins".to_string()
+        } else if solution.value == -1.0 {
+            "loses".to_string()
+        } else if solution.value == 0.0 {
+            "stalemates".to_string()
+        } else {
+            format!("scores {:.3}", solution.value)
+        };
+        println!(
+            "Player {} {} with best play",
+            initial.player_turn + 1,
+            result
+        );
+        if let Some(best_move) = solution.best_move {
+            println!("Best move: {}", describe_move(&initial, &best_move));
+        }
+        if let Some(table) = table {
+            println!(
+                "Transposition table: {} positions, {} hits",
+                table.len(),
+                table.hits
+            );
+        }
+        return Ok(());
+    }
+    if let Some(variant) = options.search {
+        let solution = search(initial.clone(), variant).map_err(|e| e.to_string())?;
+        match solution.winner {
+            Some(winner) => println!("Player {} goes out first with best play", winner + 1),
+            None => println!("No certain winner with best play"),
+        }
+        for (seat, outcome) in solution.outcome.iter().enumerate() {
+            println!("Player {}: {:.3} outcome", seat + 1, outcome);
+        }
+        let mut before = &initial;
+        for after in solution.principal_variation.iter() {
+            println!("{}", describe_move(before, after));
+            before = after;
+        }
+        return Ok(());
+    }
+    let plays_for_chips = initial.rules.chips.is_some();
+    let teams: Option<Vec<u8>> = initial.rules.partnership.map(|_| {
+        (0..initial.players.len())
+            .map(|seat| initial.team(seat as u8))
+            .collect()
+    });
+
+    let tally = match options.table_megabytes {
+        Some(megabytes) => {
+            let mut table = TranspositionTable::new(megabytes * 1024 * 1024);
+            let tally = solve(initial, &mut table, options.weighting).map_err(|e| e.to_string())?;
+            println!(
+                "Transposition table: {} positions, {} hits",
+                table.len(),
+                table.hits
+            );
+            tally
         }
+        None => match options.threads {
+            Some(threads) => explore_parallel(initial, threads, options.weighting)?,
+            None => explore(initial, options.weighting)?,
+        },
+    };
 
-        game_state = next_game_state.take();
+    // under uniform play the weights of the games add up to one
+    let outcome = match options.weighting {
+        Weighting::Leaves => "victories",
+        Weighting::UniformPlay => "win probability",
+    };
+    match teams {
+        Some(teams) => {
+            // a victory is reported for one seat of the winning team, so add up by team
+            for team in 0..(teams.len() / 2) as u8 {
+                let seats: Vec<usize> = (0..teams.len())
+                    .filter(|seat| teams[*seat] == team)
+                    .collect();
+                let team_victories: f64 = seats.iter().map(|seat| tally.victories[*seat]).sum();
+                let names: Vec<String> = seats.iter().map(|seat| (seat + 1).to_string()).collect();
+                println!(
+                    "Team {} (players {}): {} {}",
+                    team + 1,
+                    names.join(" and "),
+                    format_count(team_victories),
+                    outcome
+                );
+            }
+        }
+        None => {
+            for (seat, victories) in tally.victories.iter().enumerate() {
+                if *victories > 0.0 {
+                    println!(
+                        "Player {}: {} {}",
+                        seat + 1,
+                        format_count(*victories),
+                        outcome
+                    );
+                }
+            }
+        }
+    }
+    if tally.placings.iter().flatten().any(|count| *count > 0.0) {
+        for (seat, counts) in tally.placings.iter().enumerate() {
+            let places: Vec<String> = counts.iter().map(|count| format_count(*count)).collect();
+            println!("Player {} placings: {}", seat + 1, places.join(", "));
+        }
+    }
+    if tally.stalemates > 0.0 {
+        println!("Stalemates: {}", format_count(tally.stalemates));
+    }
+    if tally.games() > 0.0 {
+        println!(
+            "Average game length: {:.2} turns",
+            tally.turns / tally.games()
+        );
+    }
+    let leaves = tally.games() - tally.stalemates;
+    if plays_for_chips && leaves > 0.0 {
+        // averaged over the games won, each weighted as the tally weighted it: equally when
+        // counting leaves, or by its probability under uniform play
+        for (seat, total) in tally.chips.iter().enumerate() {
+            println!("Player {}: {:.3} expected chips", seat + 1, total / leaves);
+        }
     }
 
-    let mut results: HashMap<u8, usize> = HashMap::new();
+    Ok(())
+}
 
-    for v in victories {
-        let _ = match results.get(&v) {
-            Some(current) => results.insert(v, current + 1),
-            None => results.insert(v, 1),
-        };
+/// Formats a weighted count, as a whole number when no draw or choice has split it.
+fn format_count(count: f64) -> String {
+    match count.fract() == 0.0 {
+        true => format!("{}", count),
+        false => format!("{:.3}", count),
     }
+}
 
+fn play_match(options: cli::Options, target_score: u32) -> Result<(), String> {
+    let mut game_match = Match::new(
+        options.players,
+        options.rules,
+        options.scoring,
+        target_score,
+    );
+    let winner = game_match
+        .play(&mut thread_rng())
+        .map_err(|e| e.to_string())?;
+    let mut scores: Vec<u32> = vec![0; game_match.scores.len()];
+    for deal in game_match.deals.iter() {
+        match deal.winner {
+            Some(winner) => print!(
+                "Player {} deals, player {} goes out:",
+                deal.dealer + 1,
+                winner + 1
+            ),
+            None => print!("Player {} deals, stalemate:", deal.dealer + 1),
+        }
+        for (seat, penalty) in deal.penalties.iter().enumerate() {
+            scores[seat] += penalty;
+            print!(" {}", scores[seat]);
+        }
+        println!(" ({} turns)", deal.turns);
+    }
+    println!(
+        "Player {} wins after {} deals",
+        winner + 1,
+        game_match.deals.len()
+    );
     Ok(())
 }
 
+/// Queues the states a decision leads to, each with its weight, and returns the next one to
+/// explore. The weight of a draw is shared between the cards that could be drawn, and the
+/// options of a player's choice are weighted by `weighting`.
 fn process_branches(
-    branches: &mut Vec<GameState>,
+    branches: &mut Vec<(GameState, f64)>,
     decision: Decision,
-) -> Result<GameState, String> {
+    weight: f64,
+    weighting: Weighting,
+) -> Result<(GameState, f64), String> {
     match decision {
-        Decision::Victory(_) => Err("Victory decision leak".to_string()),
-        Decision::NoPlayableCards(state) => Ok(state),
-        Decision::OnePlayableCard(state) => Ok(state),
+        Decision::Victory(_, _) => Err("Victory decision leak".to_string()),
+        Decision::Stalemate => Err("Stalemate decision leak".to_string()),
+        Decision::Placings(_, _) => Err("Placings decision leak".to_string()),
+        Decision::NoPlayableCards(state) => Ok((state, weight)),
+        Decision::OnePlayableCard(state) => Ok((state, weight)),
         Decision::MultiplePlayableCards(states) => {
+            let choice = weighting.choice(weight, states.len());
+            for state in states {
+                branches.push((state, choice));
+            }
+            match branches.pop() {
+                Some(state) => Ok(state),
+                None => Err("No states in branches".to_string()),
+            }
+        }
+        Decision::Draw(states) => {
+            let chance = weight / states.len() as f64;
             for state in states {
-                branches.push(state);
+                branches.push((state, chance));
             }
             match branches.pop() {
                 Some(state) => Ok(state),
@@ -66,12 +260,111 @@ fn process_branches(
 }
 
 mod card_and_enums {
+    use std::fmt;
+    use std::str::FromStr;
+    use thiserror::Error;
+
     #[derive(Debug, Clone, PartialEq)]
     pub struct Card {
         pub suit: SuitEnum,
         pub number: NumberEnum,
     }
 
+    impl Card {
+        /// Returns a number identifying the card, the same for every copy of it.
+        pub fn index(&self) -> u8 {
+            (self.suit as u8) * 16 + self.number.rank()
+        }
+
+        /// Returns a joker. Jokers belong to no suit, so every joker is the same card.
+        pub fn joker() -> Card {
+            Card {
+                suit: SuitEnum::Spade,
+                number: NumberEnum::Joker,
+            }
+        }
+    }
+
+    #[derive(Debug, Error)]
+    pub enum CardParseError {
+        #[error("Unknown card number in '{0}'")]
+        InvalidNumber(String),
+
+        #[error("Unknown card suit in '{0}'")]
+        InvalidSuit(String),
+    }
+
+    impl FromStr for Card {
+        type Err = CardParseError;
+
+        /// Parses short card names such as `7D`, `10H` or `KS`.
+        fn from_str(s: &str) -> Result<Card, CardParseError> {
+            let s = s.trim().to_uppercase();
+            if s.len() < 2 {
+                return Err(CardParseError::InvalidNumber(s));
+            }
+            let (number, suit) = s.split_at(s.len() - 1);
+            let suit = match suit {
+                "S" => SuitEnum::Spade,
+                "C" => SuitEnum::Club,
+                "H" => SuitEnum::Heart,
+                "D" => SuitEnum::Diamond,
+                _ => return Err(CardParseError::InvalidSuit(s.clone())),
+            };
+            let number = match number.parse::<NumberEnum>() {
+                Ok(number) => number,
+                Err(_) => return Err(CardParseError::InvalidNumber(s.clone())),
+            };
+            Ok(Card { suit, number })
+        }
+    }
+
+    impl fmt::Display for Card {
+        /// Writes the card's short name, such as `7D`, `10H` or `KS`, the form it is parsed from.
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let number = match self.number {
+                NumberEnum::Joker => return write!(f, "Joker"),
+                NumberEnum::Ace => "A".to_string(),
+                NumberEnum::Jack => "J".to_string(),
+                NumberEnum::Queen => "Q".to_string(),
+                NumberEnum::King => "K".to_string(),
+                number => number.rank().to_string(),
+            };
+            let suit = match self.suit {
+                SuitEnum::Spade => "S",
+                SuitEnum::Club => "C",
+                SuitEnum::Heart => "H",
+                SuitEnum::Diamond => "D",
+            };
+            write!(f, "{}{}", number, suit)
+        }
+    }
+
+    impl FromStr for NumberEnum {
+        type Err = CardParseError;
+
+        /// Parses short number names such as `A`, `7`, `10` or `K`.
+        fn from_str(s: &str) -> Result<NumberEnum, CardParseError> {
+            let number = match s.trim().to_uppercase().as_str() {
+                "A" | "1" => NumberEnum::Ace,
+                "2" => NumberEnum::Two,
+                "3" => NumberEnum::Three,
+                "4" => NumberEnum::Four,
+                "5" => NumberEnum::Five,
+                "6" => NumberEnum::Six,
+                "7" => NumberEnum::Seven,
+                "8" => NumberEnum::Eight,
+                "9" => NumberEnum::Nine,
+                "10" | "T" => NumberEnum::Ten,
+                "J" => NumberEnum::Jack,
+                "Q" => NumberEnum::Queen,
+                "K" => NumberEnum::King,
+                _ => return Err(CardParseError::InvalidNumber(s.to_string())),
+            };
+            Ok(number)
+        }
+    }
+
     #[derive(Debug, Clone, Copy, PartialEq)]
     pub enum SuitEnum {
         Spade,
@@ -107,6 +400,8 @@ mod card_and_enums {
         Jack,
         Queen,
         King,
+        /// Only found on jokers, and left out of `iterator`.
+        Joker,
     }
 
     impl NumberEnum {
@@ -128,6 +423,19 @@ mod card_and_enums {
             ]
             .into_iter()
         }
+
+        /// Returns the rank of the number, from 1 for Ace up to 13 for King. Jokers rank 0.
+        pub fn rank(&self) -> u8 {
+            match self {
+                NumberEnum::Joker => 0,
+                _ => *self as u8 + 1,
+            }
+        }
+
+        /// Returns the number with the given rank, or `None` if it is outside 1 to 13.
+        pub fn from_rank(rank: u8) -> Option<NumberEnum> {
+            NumberEnum::iterator().find(|number| number.rank() == rank)
+        }
     }
 
     #[cfg(test)]
@@ -145,6 +453,51 @@ mod card_and_enums {
             assert!(output.contains(&SuitEnum::Heart));
         }
 
+        #[test]
+        fn card_parses_from_short_name() {
+            assert_eq!(
+                "7D".parse::<Card>().unwrap(),
+                Card {
+                    suit: SuitEnum::Diamond,
+                    number: NumberEnum::Seven
+                }
+            );
+            assert_eq!(
+                "10h".parse::<Card>().unwrap(),
+                Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::Ten
+                }
+            );
+            assert_eq!(
+                "KS".parse::<Card>().unwrap(),
+                Card {
+                    suit: SuitEnum::Spade,
+                    number: NumberEnum::King
+                }
+            );
+        }
+
+        #[test]
+        fn card_parse_rejects_unknown_names() {
+            assert!("7X".parse::<Card>().is_err());
+            assert!("14D".parse::<Card>().is_err());
+            assert!("D".parse::<Card>().is_err());
+        }
+
+        #[test]
+        fn numberenum_rank_round_trips() {
+            assert_eq!(NumberEnum::Ace.rank(), 1);
+            assert_eq!(NumberEnum::Seven.rank(), 7);
+            assert_eq!(NumberEnum::King.rank(), 13);
+            for number in NumberEnum::iterator() {
+                assert_eq!(NumberEnum::from_rank(number.rank()), Some(number));
+            }
+            assert_eq!(NumberEnum::Joker.rank(), 0);
+            assert_eq!(NumberEnum::from_rank(0), None);
+            assert_eq!(NumberEnum::from_rank(14), None);
+        }
+
         #[test]
         fn numberenum_iterator_contains_all_numbers() {
             let output: Vec<NumberEnum> = NumberEnum::iterator().collect();
@@ -167,1070 +520,5990 @@ mod card_and_enums {
     }
 }
 
-mod stack {
+mod rule_set {
     use crate::card_and_enums::{Card, NumberEnum, SuitEnum};
     use thiserror::Error;
 
-    #[derive(Debug, Clone)]
-    pub struct Stack {
-        pub suit: SuitEnum,
-        up_card: Option<Card>,
-        down_card: Option<Card>,
+    /// Which card is allowed to start the game.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum OpeningCard {
+        /// Any card that opens a stack may be played first.
+        Any,
+        /// Only this card may be played first, e.g. the seven of diamonds.
+        Card(Card),
     }
 
-    #[derive(Debug, Error)]
-    pub enum StackError {
-        #[error("Invalid stack state")]
-        InvalidStackState,
+    /// Which player takes the first turn.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum FirstPlayer {
+        /// The player sitting after the dealer starts.
+        LeftOfDealer,
+        /// The player holding the opening card starts. Requires `OpeningCard::Card`.
+        HolderOfOpeningCard,
+    }
 
-        #[error("Up position contains card lower than Seven")]
-        InvalidUpStack,
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct OpeningRule {
+        pub opening_card: OpeningCard,
+        pub first_player: FirstPlayer,
+    }
 
-        #[error("Down position contains card higher than Seven")]
-        InvalidDownStack,
+    impl OpeningRule {
+        pub fn new(opening_card: OpeningCard, first_player: FirstPlayer) -> OpeningRule {
+            OpeningRule {
+                opening_card,
+                first_player,
+            }
+        }
+    }
 
-        #[error("Attempted to play on a completed stack")]
-        CompletedStackPlayedOn,
+    impl Default for OpeningRule {
+        fn default() -> OpeningRule {
+            OpeningRule::new(OpeningCard::Any, FirstPlayer::LeftOfDealer)
+        }
+    }
 
-        #[error("Attempted to play a card with an unplayable number")]
-        UnplayableCardNumber,
+    /// Whether a player may knock while holding a playable card.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum PlayRule {
+        /// A player must play if they can, and knocks only when they cannot.
+        Forced,
+        /// A player may knock instead of playing, e.g. to block an opponent.
+        OptionalKnock(StalemateResolution),
     }
 
-    impl Stack {
-        pub fn new(suit: SuitEnum) -> Stack {
-            return Stack {
-                suit: suit,
-                up_card: None,
-                down_card: None,
-            };
-        }
+    /// What happens when every player has knocked in a row, leaving the board unchanged.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum StalemateResolution {
+        /// The game ends with no winner.
+        Draw,
+        /// Knocking is no longer optional: players must play until someone does. If a full
+        /// round of forced knocks follows, the game ends with no winner.
+        ForcedPlay,
+        /// The player holding the fewest cards wins, the earliest seat breaking ties.
+        FewestCards,
+    }
 
-        pub fn get_playable_cards(&self) -> Result<Option<Vec<Card>>, StackError> {
-            match (self.up_card.is_some(), self.down_card.is_some()) {
-                (false, false) => {
-                    // if nothing has been played, then only the seven is playable
-                    return Ok(Some(vec![Card {
-                        suit: self.suit,
-                        number: NumberEnum::Seven,
-                    }]));
-                }
-                (true, true) => {
-                    // if at least the seven has been played, then return the next playable card on each stack,
-                    // or None if the direction is complete
-                    let playable_up: Option<Card> = match self.up_card.clone().unwrap().number {
-                        NumberEnum::Seven => Some(Card {
-                            suit: self.suit,
-                            number: NumberEnum::Eight,
-                        }),
-                        NumberEnum::Eight => Some(Card {
-                            suit: self.suit,
-                            number: NumberEnum::Nine,
-                        }),
-                        NumberEnum::Nine => Some(Card {
-                            suit: self.suit,
-                            number: NumberEnum::Ten,
-                        }),
-                        NumberEnum::Ten => Some(Card {
-                            suit: self.suit,
-                            number: NumberEnum::Jack,
-                        }),
-                        NumberEnum::Jack => Some(Card {
-                            suit: self.suit,
-                            number: NumberEnum::Queen,
-                        }),
-                        NumberEnum::Queen => Some(Card {
-                            suit: self.suit,
-                            number: NumberEnum::King,
-                        }),
-                        NumberEnum::King => None,
-                        _ => return Err(StackError::InvalidUpStack),
-                    };
-                    let playable_down: Option<Card> = match self.down_card.clone().unwrap().number {
-                        NumberEnum::Ace => None,
-                        NumberEnum::Two => Some(Card {
-                            suit: self.suit,
-                            number: NumberEnum::Ace,
-                        }),
-                        NumberEnum::Three => Some(Card {
-                            suit: self.suit,
-                            number: NumberEnum::Two,
-                        }),
-                        NumberEnum::Four => Some(Card {
-                            suit: self.suit,
-                            number: NumberEnum::Three,
-                        }),
-                        NumberEnum::Five => Some(Card {
-                            suit: self.suit,
-                            number: NumberEnum::Four,
-                        }),
-                        NumberEnum::Six => Some(Card {
-                            suit: self.suit,
-                            number: NumberEnum::Five,
-                        }),
-                        NumberEnum::Seven => Some(Card {
-                            suit: self.suit,
-                            number: NumberEnum::Six,
-                        }),
-                        _ => return Err(StackError::InvalidDownStack),
-                    };
-                    match (playable_up.is_some(), playable_down.is_some()) {
-                        (true, true) => {
-                            return Ok(Some(vec![playable_up.unwrap(), playable_down.unwrap()]))
-                        }
-                        (true, false) => return Ok(Some(vec![playable_up.unwrap()])),
-                        (false, true) => return Ok(Some(vec![playable_down.unwrap()])),
-                        (false, false) => return Ok(None),
-                    }
+    /// Which cards make up the deck. Aces always rank low, so in a deck without the low
+    /// numbers the Ace plays straight after the lowest number left in its suit.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum DeckSpec {
+        /// The 52-card deck: four suits of Ace to King.
+        Standard,
+        /// The 32-card piquet deck: Ace and Seven to King in each suit.
+        Piquet,
+        /// The 36-card deck: Ace and Six to King in each suit.
+        ThirtySix,
+        /// The 40-card Spanish deck: Ace to Seven and Jack to King in each suit.
+        Spanish,
+        /// Any set of cards, such as a few numbers of two suits, small enough to study
+        /// exhaustively.
+        Custom(Vec<Card>),
+    }
+
+    impl DeckSpec {
+        /// Returns every card in the deck, in suit then number order.
+        pub fn cards(&self) -> Vec<Card> {
+            let ranks: Vec<u8> = match self {
+                DeckSpec::Standard => (1..=13).collect(),
+                DeckSpec::Piquet => [1].into_iter().chain(7..=13).collect(),
+                DeckSpec::ThirtySix => [1].into_iter().chain(6..=13).collect(),
+                DeckSpec::Spanish => (1..=7).chain(11..=13).collect(),
+                DeckSpec::Custom(cards) => return cards.clone(),
+            };
+            let mut cards = Vec::new();
+            for suit in SuitEnum::iterator() {
+                for number in ranks.iter().filter_map(|rank| NumberEnum::from_rank(*rank)) {
+                    cards.push(Card { suit, number });
                 }
-                _ => return Err(StackError::InvalidStackState),
             }
+            cards
         }
 
-        pub fn play_card(&mut self, card_number: NumberEnum) -> Result<(), StackError> {
-            let playable_cards = match self.get_playable_cards()? {
-                Some(cards) => cards,
-                None => return Err(StackError::CompletedStackPlayedOn),
-            }; // get playable card(s), if none, then stack is complete
-            if playable_cards.contains(&Card {
-                suit: self.suit,
-                number: card_number,
-            }) {
-                // if the card is playable
-                match card_number {
-                    NumberEnum::Ace
-                    | NumberEnum::Two
-                    | NumberEnum::Three
-                    | NumberEnum::Four
-                    | NumberEnum::Five
-                    | NumberEnum::Six => {
-                        // if the card is below seven, it plays on the downwards stack
-                        self.down_card = Some(Card {
-                            suit: self.suit,
-                            number: card_number,
-                        });
-                        return Ok(());
-                    }
-                    NumberEnum::Seven => {
-                        // handle the special case for seven, which plays on both stacks
-                        self.up_card = Some(Card {
-                            suit: self.suit,
-                            number: card_number,
-                        });
-                        self.down_card = Some(Card {
-                            suit: self.suit,
-                            number: card_number,
-                        });
-                        return Ok(());
-                    }
-                    NumberEnum::Eight
-                    | NumberEnum::Nine
-                    | NumberEnum::Ten
-                    | NumberEnum::Jack
-                    | NumberEnum::Queen
-                    | NumberEnum::King => {
-                        // if the card is greater than seven, it plays on the up stack
-                        self.up_card = Some(Card {
-                            suit: self.suit,
-                            number: card_number,
-                        });
-                        return Ok(());
-                    }
+        /// Returns the numbers of `suit` in the deck, from lowest to highest.
+        pub fn numbers(&self, suit: SuitEnum) -> Vec<NumberEnum> {
+            let mut numbers: Vec<NumberEnum> = Vec::new();
+            for card in self.cards() {
+                if card.suit == suit && !numbers.contains(&card.number) {
+                    numbers.push(card.number);
                 }
-            } else {
-                // not contained in the playable cards, therefore an unplayable number
-                return Err(StackError::UnplayableCardNumber);
             }
+            numbers.sort_by_key(|number| number.rank());
+            numbers
         }
+    }
 
-        #[cfg(test)]
-        pub fn from(
-            suit: SuitEnum,
-            up_card: Option<Card>,
-            down_card: Option<Card>,
-        ) -> Result<Stack, StackError> {
-            let output = Stack {
-                suit: suit,
-                up_card: up_card,
-                down_card: down_card,
-            };
-            match output.get_playable_cards() {
-                Ok(_) => Ok(output),
-                Err(e) => Err(e),
-            }
-        }
+    /// How the shuffled deck is dealt to the players, and who gets any extra cards when the
+    /// deck does not divide evenly.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum DealingScheme {
+        /// One card at a time to each player in turn from the first seat, until the deck runs
+        /// out. The lowest seats get the extra cards.
+        RoundRobin,
+        /// One card at a time from the player on the dealer's left, until the deck runs out.
+        /// The players nearest the dealer's left get the extra cards.
+        FromDealersLeft,
+        /// Packets of this many cards at a time from the dealer's left, until the deck runs
+        /// out. Whoever is dealt the last, short packet, and every player dealt a full packet
+        /// in the last round, end up with more cards.
+        Packets(u8),
+        /// An even share each from the dealer's left. The extra cards are turned face up as a
+        /// widow, and are laid on the table as soon as they can be played, from the deal on.
+        /// If the widow holds the opening card, the player on the dealer's left leads.
+        Widow,
+        /// An even share each from the dealer's left. The extra cards go to the player who
+        /// leads, or to the player on the dealer's left if they include the opening card.
+        ExtrasToLeader,
+    }
 
-        #[cfg(test)]
-        pub fn get_completed_stack(suit: SuitEnum) -> Stack {
-            return Stack {
-                suit: suit,
-                up_card: Some(Card {
-                    suit: suit,
-                    number: NumberEnum::King,
-                }),
-                down_card: Some(Card {
-                    suit: suit,
-                    number: NumberEnum::Ace,
-                }),
-            };
-        }
+    /// When a game is over.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum EndCondition {
+        /// The game ends as soon as one player has no cards left.
+        FirstOut,
+        /// Players who go out are skipped, and the game carries on until only one player
+        /// holds cards, giving a full finishing order.
+        FullOrder,
     }
 
-    #[cfg(test)]
-    mod test {
+    /// When cards other than the centre card can be played on a row.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum BuildingRule {
+        /// As soon as the row's own centre card is down.
+        OwnCentre,
+        /// Only once every row on the table has its centre card down.
+        AllCentres,
+        /// Only once the row above has its centre card down. Rows are laid out Spades,
+        /// Clubs, Hearts then Diamonds, and the top row has no row above it.
+        RowAbove,
+    }
 
-        use super::*;
+    /// When a team wins in a partnership game. Partners sit opposite each other.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum PartnershipWin {
+        /// As soon as either partner goes out.
+        FirstPartnerOut,
+        /// Once both partners are out. A player who has gone out is skipped while their
+        /// partner plays on.
+        BothPartnersOut,
+    }
 
-        #[test]
-        fn initialization() {
-            let stack = Stack::new(SuitEnum::Spade);
-            assert!(stack.up_card.is_none());
-            assert!(stack.down_card.is_none());
-            assert_eq!(stack.suit, SuitEnum::Spade);
-        }
+    /// A card that changes hands when a player knocks. A transfer never takes a player's last
+    /// card, so only playing a card can put a player out.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum KnockPenalty {
+        /// The knocking player takes a card of their choice from the player who played last.
+        TakeFromLast,
+        /// The knocking player gives a card of their choice to the next player holding cards.
+        GiveToNext,
+    }
 
-        mod test_get_playable_cards {
+    /// A play that earns the player an immediate extra turn.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum BonusTurn {
+        /// Finishing one direction of a row, such as by laying its King or Ace.
+        CompletedDirection,
+        /// Laying a row's centre card.
+        Centre,
+    }
 
-            use super::*;
+    /// Which number each suit's row is started from.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum CentreRule {
+        /// Every row starts from this number.
+        Fixed(NumberEnum),
+        /// Domino: any card may be played first, and its number becomes the centre of every
+        /// row.
+        FirstCard,
+    }
 
-            #[test]
-            fn only_seven_plays_on_new_stack() {
-                let stack = Stack::new(SuitEnum::Club);
-                let playable_cards = stack
-                    .get_playable_cards()
-                    .expect("Error in Stack::get_playable_cards");
-                assert!(playable_cards.is_some());
+    /// Whether a row can run on past the King and Ace.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum SequenceRule {
+        /// A row builds up to the King and down to the Ace, and stops there.
+        Linear,
+        /// Ranks form a cycle: the King is followed by the Ace, and the Ace by the King going
+        /// down. A row is complete once its two ends meet.
+        Wraparound,
+    }
 
-                let playable_cards = playable_cards.unwrap();
-                assert_eq!(playable_cards.len(), 1);
-                assert!(playable_cards.contains(&Card {
-                    suit: SuitEnum::Club,
-                    number: NumberEnum::Seven
-                }));
-            }
+    /// What happens to a wild card when the card it stands in for is played on it.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum WildReplacement {
+        /// The player laying the real card takes the wild card into their hand.
+        Reclaimed,
+        /// The wild card stays on the table, out of play.
+        LeftOnTable,
+    }
 
-            #[test]
-            fn eight_and_six_play_on_seven() {
-                let mut stack = Stack::new(SuitEnum::Diamond);
-                let seven = Card {
-                    suit: SuitEnum::Diamond,
-                    number: NumberEnum::Seven,
-                };
-                stack.up_card = Some(seven.clone());
-                stack.down_card = Some(seven);
+    /// Wild cards, which can be played as the next card on either end of any opened row.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct WildRule {
+        /// Number of jokers shuffled into the deck.
+        pub jokers: u8,
+        /// A number whose cards are also wild, as well as playing as themselves.
+        pub wild_number: Option<NumberEnum>,
+        pub replacement: WildReplacement,
+    }
 
-                let playable_cards = stack
-                    .get_playable_cards()
-                    .expect("Error in Stack::get_playable_cards")
-                    .unwrap();
+    impl WildRule {
+        pub fn is_wild(&self, card: &Card) -> bool {
+            card.number == NumberEnum::Joker || Some(card.number) == self.wild_number
+        }
+    }
 
-                assert_eq!(playable_cards.len(), 2);
-                assert!(playable_cards.contains(&Card {
-                    suit: SuitEnum::Diamond,
-                    number: NumberEnum::Six
-                }));
-                assert!(playable_cards.contains(&Card {
-                    suit: SuitEnum::Diamond,
-                    number: NumberEnum::Eight
-                }));
-            }
+    /// Chip payments for the Fan Tan (Parliament) betting variant. Every player antes into
+    /// the pot and pays into it again on each knock. The winner takes the pot, and each loser
+    /// also pays the winner for every card left in their hand.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct ChipRule {
+        pub ante: u32,
+        pub knock_fee: u32,
+        pub per_card: u32,
+    }
 
-            #[test]
-            fn finished_up_stack_does_not_return_playable() {
-                let mut stack = Stack::new(SuitEnum::Heart);
-                let seven = Card {
-                    suit: SuitEnum::Heart,
-                    number: NumberEnum::Seven,
-                };
-                let king = Card {
-                    suit: SuitEnum::Heart,
-                    number: NumberEnum::King,
-                };
-                stack.up_card = Some(king);
-                stack.down_card = Some(seven);
+    /// How the rows of a suit are laid out when more than one deck is used.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum DuplicateRows {
+        /// Each copy of a suit builds its own row, opened by its own centre card.
+        Separate,
+        /// All copies of a suit share one row. A duplicate can be laid on its twin any time
+        /// after the first copy has been played.
+        Shared,
+    }
 
-                let playable_cards = stack
-                    .get_playable_cards()
-                    .expect("Error in Stack::get_playable_cards")
-                    .unwrap();
+    /// Every rule a game of Sevens is played by.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct RuleSet {
+        pub opening: OpeningRule,
+        pub play: PlayRule,
+        /// A card passed on each knock, if knocking is penalised.
+        pub knock_penalty: Option<KnockPenalty>,
+        /// Whether a player may lay a run of consecutive cards of one suit in a single turn.
+        pub runs: bool,
+        /// A play that earns another turn straight away, if any does.
+        pub bonus_turn: Option<BonusTurn>,
+        pub deck: DeckSpec,
+        pub dealing: DealingScheme,
+        pub end: EndCondition,
+        /// Partnership play, if players are in teams.
+        pub partnership: Option<PartnershipWin>,
+        pub centre: CentreRule,
+        pub sequence: SequenceRule,
+        pub building: BuildingRule,
+        /// Chip payments, if the game is played for chips.
+        pub chips: Option<ChipRule>,
+        pub wilds: Option<WildRule>,
+        /// Number of decks shuffled together.
+        pub decks: u8,
+        pub duplicate_rows: DuplicateRows,
+        /// Cards dealt to each player, with the rest left face down as a stock. The whole
+        /// deck is dealt if not set.
+        pub hand_size: Option<u8>,
+    }
 
-                assert_eq!(playable_cards.len(), 1);
-                assert!(playable_cards.contains(&Card {
-                    suit: SuitEnum::Heart,
-                    number: NumberEnum::Six
-                }));
-            }
+    #[derive(Debug, Error)]
+    pub enum RuleSetError {
+        #[error("Opening card {0:?} is not a centre card")]
+        OpeningCardNotCentre(Card),
 
-            #[test]
-            fn finished_down_stack_does_not_return_playable() {
-                let mut stack = Stack::new(SuitEnum::Heart);
-                let seven = Card {
-                    suit: SuitEnum::Heart,
-                    number: NumberEnum::Seven,
-                };
-                let ace = Card {
-                    suit: SuitEnum::Heart,
-                    number: NumberEnum::Ace,
-                };
-                stack.up_card = Some(seven);
-                stack.down_card = Some(ace);
+        #[error("Opening card {0:?} is not in the deck")]
+        OpeningCardNotInDeck(Card),
 
-                let playable_cards = stack
-                    .get_playable_cards()
-                    .expect("Error in Stack::get_playable_cards")
-                    .unwrap();
+        #[error("At least one deck is needed")]
+        NoDecks,
 
-                assert_eq!(playable_cards.len(), 1);
-                assert!(playable_cards.contains(&Card {
-                    suit: SuitEnum::Heart,
-                    number: NumberEnum::Eight
-                }));
-            }
+        #[error("The deck has no cards")]
+        EmptyDeck,
 
-            #[test]
-            fn completed_stack_does_not_return_playable() {
-                let mut stack = Stack::new(SuitEnum::Heart);
-                let king = Card {
-                    suit: SuitEnum::Heart,
-                    number: NumberEnum::King,
-                };
-                let ace = Card {
-                    suit: SuitEnum::Heart,
-                    number: NumberEnum::Ace,
-                };
-                stack.up_card = Some(king);
-                stack.down_card = Some(ace);
+        #[error("Cards cannot be dealt in packets of zero")]
+        EmptyPackets,
 
-                let playable_cards = stack
-                    .get_playable_cards()
-                    .expect("Error in Stack::get_playable_cards");
+        #[error("Hands of zero cards cannot be dealt")]
+        EmptyHands,
 
-                assert!(playable_cards.is_none());
-            }
+        #[error("Centre number {0:?} is missing from a suit in the deck")]
+        CentreNotInDeck(NumberEnum),
 
-            #[test]
-            fn invalid_stack_state() {
-                let mut stack = Stack::new(SuitEnum::Heart);
-                let ace = Card {
-                    suit: SuitEnum::Heart,
-                    number: NumberEnum::Ace,
-                };
-                stack.down_card = Some(ace);
+        #[error(
+            "The first card cannot set the centre when the deck's suits hold different numbers"
+        )]
+        UnevenSuits,
 
-                let output = stack.get_playable_cards();
+        #[error("Wraparound rows must be shared when playing with several decks")]
+        SeparateWraparoundRows,
+    }
 
-                assert!(output.is_err());
-                assert_eq!(
-                    output.unwrap_err().to_string(),
-                    StackError::InvalidStackState.to_string()
-                )
+    impl RuleSet {
+        pub fn builder() -> RuleSetBuilder {
+            RuleSetBuilder::new()
+        }
+
+        /// Returns every card used in the game, with one copy per deck, and any jokers.
+        pub fn deck_cards(&self) -> Vec<Card> {
+            let mut output = Vec::new();
+            for _deck in 0..self.decks {
+                output.append(&mut self.deck.cards());
+            }
+            if let Some(wilds) = self.wilds {
+                for _joker in 0..wilds.jokers {
+                    output.push(Card::joker());
+                }
             }
+            output
+        }
 
-            #[test]
-            fn invalid_up_state() {
-                let mut stack = Stack::new(SuitEnum::Heart);
-                let ace = Card {
-                    suit: SuitEnum::Heart,
-                    number: NumberEnum::Ace,
-                };
-                stack.up_card = Some(ace.clone());
-                stack.down_card = Some(ace);
+        /// Returns the largest table the deck can deal at least two cards each to.
+        pub fn max_players(&self) -> usize {
+            self.deck_cards().len() / 2
+        }
 
-                let output = stack.get_playable_cards();
+        /// Returns how many rows each suit is laid out in.
+        pub fn rows_per_suit(&self) -> u8 {
+            match self.duplicate_rows {
+                DuplicateRows::Separate => self.decks,
+                DuplicateRows::Shared => 1,
+            }
+        }
 
-                assert!(output.is_err());
-                assert_eq!(
-                    output.unwrap_err().to_string(),
-                    StackError::InvalidUpStack.to_string()
-                )
+        /// Returns how many copies of each card can be played on a single row.
+        pub fn copies_per_row(&self) -> u8 {
+            match self.duplicate_rows {
+                DuplicateRows::Separate => 1,
+                DuplicateRows::Shared => self.decks,
             }
+        }
+    }
 
-            #[test]
-            fn invalid_down_state() {
-                let mut stack = Stack::new(SuitEnum::Heart);
-                let king = Card {
-                    suit: SuitEnum::Heart,
-                    number: NumberEnum::King,
-                };
-                stack.down_card = Some(king.clone());
-                stack.up_card = Some(king);
+    impl Default for RuleSet {
+        fn default() -> RuleSet {
+            RuleSet {
+                opening: OpeningRule::default(),
+                play: PlayRule::Forced,
+                knock_penalty: None,
+                runs: false,
+                bonus_turn: None,
+                deck: DeckSpec::Standard,
+                dealing: DealingScheme::RoundRobin,
+                end: EndCondition::FirstOut,
+                partnership: None,
+                centre: CentreRule::Fixed(NumberEnum::Seven),
+                sequence: SequenceRule::Linear,
+                building: BuildingRule::OwnCentre,
+                chips: None,
+                wilds: None,
+                decks: 1,
+                duplicate_rows: DuplicateRows::Separate,
+                hand_size: None,
+            }
+        }
+    }
 
-                let output = stack.get_playable_cards();
+    /// Builds a `RuleSet`, starting from the standard rules.
+    #[derive(Debug, Clone, Default)]
+    pub struct RuleSetBuilder {
+        rules: RuleSet,
+    }
 
-                assert!(output.is_err());
-                assert_eq!(
-                    output.unwrap_err().to_string(),
-                    StackError::InvalidDownStack.to_string()
-                )
+    impl RuleSetBuilder {
+        pub fn new() -> RuleSetBuilder {
+            RuleSetBuilder {
+                rules: RuleSet::default(),
             }
         }
 
-        mod test_play_card {
+        pub fn opening(mut self, opening: OpeningRule) -> RuleSetBuilder {
+            self.rules.opening = opening;
+            self
+        }
 
-            use super::*;
+        pub fn play(mut self, play: PlayRule) -> RuleSetBuilder {
+            self.rules.play = play;
+            self
+        }
 
-            #[test]
-            fn seven_plays_on_new_stack() {
-                let mut stack = Stack::new(SuitEnum::Club);
-                let seven = Card {
-                    suit: SuitEnum::Club,
-                    number: NumberEnum::Seven,
-                };
+        pub fn knock_penalty(mut self, knock_penalty: KnockPenalty) -> RuleSetBuilder {
+            self.rules.knock_penalty = Some(knock_penalty);
+            self
+        }
 
-                let output = stack.play_card(NumberEnum::Seven);
+        pub fn runs(mut self, runs: bool) -> RuleSetBuilder {
+            self.rules.runs = runs;
+            self
+        }
 
-                assert!(output.is_ok());
-                assert_eq!(stack.up_card.unwrap(), seven);
-                assert_eq!(stack.down_card.unwrap(), seven);
-            }
+        pub fn bonus_turn(mut self, bonus_turn: BonusTurn) -> RuleSetBuilder {
+            self.rules.bonus_turn = Some(bonus_turn);
+            self
+        }
 
-            #[test]
-            fn eight_plays_on_up_stack_with_seven() {
-                let mut stack = Stack::new(SuitEnum::Club);
-                let seven = Card {
-                    suit: SuitEnum::Club,
-                    number: NumberEnum::Seven,
-                };
-                let eight = Card {
-                    suit: SuitEnum::Club,
-                    number: NumberEnum::Eight,
-                };
-                stack.up_card = Some(seven.clone());
-                stack.down_card = Some(seven.clone());
+        pub fn deck(mut self, deck: DeckSpec) -> RuleSetBuilder {
+            self.rules.deck = deck;
+            self
+        }
 
-                let output = stack.play_card(NumberEnum::Eight);
+        pub fn dealing(mut self, dealing: DealingScheme) -> RuleSetBuilder {
+            self.rules.dealing = dealing;
+            self
+        }
 
-                assert!(output.is_ok());
-                assert_eq!(stack.up_card.unwrap(), eight);
-                assert_eq!(stack.down_card.unwrap(), seven);
-            }
+        pub fn end(mut self, end: EndCondition) -> RuleSetBuilder {
+            self.rules.end = end;
+            self
+        }
 
-            #[test]
-            fn six_plays_on_down_stack_with_seven() {
-                let mut stack = Stack::new(SuitEnum::Club);
-                let seven = Card {
-                    suit: SuitEnum::Club,
-                    number: NumberEnum::Seven,
-                };
-                let six = Card {
-                    suit: SuitEnum::Club,
-                    number: NumberEnum::Six,
-                };
-                stack.up_card = Some(seven.clone());
-                stack.down_card = Some(seven.clone());
+        pub fn partnership(mut self, partnership: PartnershipWin) -> RuleSetBuilder {
+            self.rules.partnership = Some(partnership);
+            self
+        }
 
-                let output = stack.play_card(NumberEnum::Six);
+        pub fn centre(mut self, centre: CentreRule) -> RuleSetBuilder {
+            self.rules.centre = centre;
+            self
+        }
 
-                assert!(output.is_ok());
-                assert_eq!(stack.up_card.unwrap(), seven);
-                assert_eq!(stack.down_card.unwrap(), six);
-            }
+        pub fn sequence(mut self, sequence: SequenceRule) -> RuleSetBuilder {
+            self.rules.sequence = sequence;
+            self
+        }
 
-            #[test]
-            fn unplayable_card_returns_unplayablecard() {
-                let mut stack = Stack::new(SuitEnum::Club);
-                let seven = Card {
-                    suit: SuitEnum::Club,
-                    number: NumberEnum::Seven,
-                };
-                stack.up_card = Some(seven.clone());
-                stack.down_card = Some(seven.clone());
+        pub fn building(mut self, building: BuildingRule) -> RuleSetBuilder {
+            self.rules.building = building;
+            self
+        }
 
-                let output = stack.play_card(NumberEnum::King);
+        pub fn chips(mut self, chips: ChipRule) -> RuleSetBuilder {
+            self.rules.chips = Some(chips);
+            self
+        }
 
-                assert!(output.is_err());
-                assert_eq!(
-                    output.unwrap_err().to_string(),
-                    StackError::UnplayableCardNumber.to_string()
-                );
-                assert_eq!(stack.up_card.unwrap(), seven);
-                assert_eq!(stack.down_card.unwrap(), seven);
-            }
+        pub fn wilds(mut self, wilds: WildRule) -> RuleSetBuilder {
+            self.rules.wilds = Some(wilds);
+            self
+        }
 
-            #[test]
-            fn playing_on_completed_stack_returns_completedstackplayedon() {
-                let mut stack = Stack::new(SuitEnum::Club);
-                let ace = Card {
-                    suit: SuitEnum::Club,
-                    number: NumberEnum::Ace,
-                };
-                let king = Card {
-                    suit: SuitEnum::Club,
-                    number: NumberEnum::King,
-                };
-                stack.up_card = Some(king.clone());
-                stack.down_card = Some(ace.clone());
+        pub fn decks(mut self, decks: u8, duplicate_rows: DuplicateRows) -> RuleSetBuilder {
+            self.rules.decks = decks;
+            self.rules.duplicate_rows = duplicate_rows;
+            self
+        }
 
-                let output = stack.play_card(NumberEnum::King);
+        pub fn hand_size(mut self, hand_size: u8) -> RuleSetBuilder {
+            self.rules.hand_size = Some(hand_size);
+            self
+        }
 
-                assert!(output.is_err());
-                assert_eq!(
-                    output.unwrap_err().to_string(),
-                    StackError::CompletedStackPlayedOn.to_string()
-                );
-                assert_eq!(stack.up_card.unwrap(), king);
-                assert_eq!(stack.down_card.unwrap(), ace);
+        /// Checks the rules are consistent with each other and returns them.
+        pub fn build(self) -> Result<RuleSet, RuleSetError> {
+            if self.rules.decks == 0 {
+                return Err(RuleSetError::NoDecks);
+            }
+            let cards = self.rules.deck.cards();
+            if cards.is_empty() {
+                return Err(RuleSetError::EmptyDeck);
+            }
+            if self.rules.sequence == SequenceRule::Wraparound
+                && self.rules.duplicate_rows == DuplicateRows::Separate
+                && self.rules.decks > 1
+            {
+                // a card could extend the up end of one copy of its row or the down end of
+                // another, but only the first row that accepts it is ever played on
+                return Err(RuleSetError::SeparateWraparoundRows);
+            }
+            if self.rules.dealing == DealingScheme::Packets(0) {
+                return Err(RuleSetError::EmptyPackets);
+            }
+            if self.rules.hand_size == Some(0) {
+                return Err(RuleSetError::EmptyHands);
+            }
+            if let CentreRule::Fixed(centre) = self.rules.centre {
+                // every suit in the deck must be able to open
+                for card in cards.iter() {
+                    if !self.rules.deck.numbers(card.suit).contains(&centre) {
+                        return Err(RuleSetError::CentreNotInDeck(centre));
+                    }
+                }
+            }
+            if self.rules.centre == CentreRule::FirstCard {
+                // the first card played sets the centre of every row, so a suit missing that
+                // number could never be opened
+                let numbers = self.rules.deck.numbers(cards[0].suit);
+                for card in cards.iter() {
+                    if self.rules.deck.numbers(card.suit) != numbers {
+                        return Err(RuleSetError::UnevenSuits);
+                    }
+                }
+            }
+            if let OpeningCard::Card(ref card) = self.rules.opening.opening_card {
+                if let CentreRule::Fixed(centre) = self.rules.centre {
+                    if card.number != centre {
+                        return Err(RuleSetError::OpeningCardNotCentre(card.clone()));
+                    }
+                }
+                if !cards.contains(card) {
+                    return Err(RuleSetError::OpeningCardNotInDeck(card.clone()));
+                }
+            }
+            Ok(self.rules)
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::test_helpers::custom_deck;
+
+        #[test]
+        fn standard_deck_has_52_distinct_cards() {
+            let cards = DeckSpec::Standard.cards();
+
+            assert_eq!(cards.len(), 52);
+            for (index, card) in cards.iter().enumerate() {
+                assert!(!cards[index + 1..].contains(card));
+            }
+        }
+
+        #[test]
+        fn stripped_decks_have_expected_numbers() {
+            assert_eq!(DeckSpec::Piquet.cards().len(), 32);
+            assert_eq!(DeckSpec::ThirtySix.cards().len(), 36);
+            assert_eq!(DeckSpec::Spanish.cards().len(), 40);
+            assert_eq!(
+                DeckSpec::Spanish.numbers(SuitEnum::Club),
+                vec![
+                    NumberEnum::Ace,
+                    NumberEnum::Two,
+                    NumberEnum::Three,
+                    NumberEnum::Four,
+                    NumberEnum::Five,
+                    NumberEnum::Six,
+                    NumberEnum::Seven,
+                    NumberEnum::Jack,
+                    NumberEnum::Queen,
+                    NumberEnum::King,
+                ]
+            );
+        }
+
+        #[test]
+        fn custom_deck_numbers_are_per_suit() {
+            let deck = custom_deck("8H,6H,7S");
+
+            assert_eq!(
+                deck.numbers(SuitEnum::Heart),
+                vec![NumberEnum::Six, NumberEnum::Eight]
+            );
+            assert!(deck.numbers(SuitEnum::Club).is_empty());
+        }
+
+        #[test]
+        fn builder_rejects_centre_missing_from_deck() {
+            let output = RuleSet::builder().deck(custom_deck("8H")).build();
+
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                RuleSetError::CentreNotInDeck(NumberEnum::Seven).to_string()
+            );
+        }
+
+        #[test]
+        fn builder_rejects_uneven_suits_when_first_card_sets_centre() {
+            let output = RuleSet::builder()
+                .centre(CentreRule::FirstCard)
+                .deck(custom_deck("7H,8H,6S,5S"))
+                .build();
+
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                RuleSetError::UnevenSuits.to_string()
+            );
+            assert!(RuleSet::builder()
+                .centre(CentreRule::FirstCard)
+                .deck(custom_deck("7H,8H,7S,8S"))
+                .build()
+                .is_ok());
+        }
+
+        #[test]
+        fn builder_rejects_empty_deck() {
+            let output = RuleSet::builder().deck(DeckSpec::Custom(vec![])).build();
+
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                RuleSetError::EmptyDeck.to_string()
+            );
+        }
+
+        #[test]
+        fn default_rules_allow_26_players() {
+            assert_eq!(RuleSet::default().max_players(), 26);
+        }
+
+        #[test]
+        fn two_decks_double_the_cards_and_players() {
+            let rules = RuleSet::builder()
+                .decks(2, DuplicateRows::Separate)
+                .build()
+                .unwrap();
+
+            assert_eq!(rules.deck_cards().len(), 104);
+            assert_eq!(rules.max_players(), 52);
+            assert_eq!(rules.rows_per_suit(), 2);
+            assert_eq!(rules.copies_per_row(), 1);
+        }
+
+        #[test]
+        fn builder_rejects_separate_wraparound_rows() {
+            let output = RuleSet::builder()
+                .sequence(SequenceRule::Wraparound)
+                .decks(2, DuplicateRows::Separate)
+                .build();
+
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                RuleSetError::SeparateWraparoundRows.to_string()
+            );
+            assert!(RuleSet::builder()
+                .sequence(SequenceRule::Wraparound)
+                .decks(2, DuplicateRows::Shared)
+                .build()
+                .is_ok());
+            assert!(RuleSet::builder()
+                .sequence(SequenceRule::Wraparound)
+                .decks(1, DuplicateRows::Separate)
+                .build()
+                .is_ok());
+        }
+
+        #[test]
+        fn shared_rows_hold_every_copy() {
+            let rules = RuleSet::builder()
+                .decks(2, DuplicateRows::Shared)
+                .build()
+                .unwrap();
+
+            assert_eq!(rules.rows_per_suit(), 1);
+            assert_eq!(rules.copies_per_row(), 2);
+        }
+
+        #[test]
+        fn jokers_are_added_to_the_deck() {
+            let rules = RuleSet::builder()
+                .wilds(WildRule {
+                    jokers: 2,
+                    wild_number: None,
+                    replacement: WildReplacement::Reclaimed,
+                })
+                .build()
+                .unwrap();
+            let cards = rules.deck_cards();
+
+            assert_eq!(cards.len(), 54);
+            assert_eq!(
+                cards.iter().filter(|card| **card == Card::joker()).count(),
+                2
+            );
+        }
+
+        #[test]
+        fn wild_number_cards_are_wild() {
+            let wilds = WildRule {
+                jokers: 0,
+                wild_number: Some(NumberEnum::Two),
+                replacement: WildReplacement::LeftOnTable,
+            };
+
+            assert!(wilds.is_wild(&Card::joker()));
+            assert!(wilds.is_wild(&Card {
+                suit: SuitEnum::Club,
+                number: NumberEnum::Two,
+            }));
+            assert!(!wilds.is_wild(&Card {
+                suit: SuitEnum::Club,
+                number: NumberEnum::Three,
+            }));
+        }
+
+        #[test]
+        fn builder_rejects_zero_decks() {
+            let output = RuleSet::builder().decks(0, DuplicateRows::Shared).build();
+
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                RuleSetError::NoDecks.to_string()
+            );
+        }
+
+        #[test]
+        fn builder_without_changes_gives_default_rules() {
+            let rules = RuleSet::builder().build().unwrap();
+
+            assert_eq!(rules, RuleSet::default());
+        }
+
+        #[test]
+        fn builder_sets_rules() {
+            let opening = OpeningRule::new(
+                OpeningCard::Card(Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::Eight,
+                }),
+                FirstPlayer::HolderOfOpeningCard,
+            );
+            let rules = RuleSet::builder()
+                .centre(CentreRule::Fixed(NumberEnum::Eight))
+                .opening(opening.clone())
+                .build()
+                .unwrap();
+
+            assert_eq!(rules.centre, CentreRule::Fixed(NumberEnum::Eight));
+            assert_eq!(rules.opening, opening);
+        }
+
+        #[test]
+        fn builder_rejects_opening_card_off_centre() {
+            let seven_of_hearts = Card {
+                suit: SuitEnum::Heart,
+                number: NumberEnum::Seven,
+            };
+            let output = RuleSet::builder()
+                .centre(CentreRule::Fixed(NumberEnum::Eight))
+                .opening(OpeningRule::new(
+                    OpeningCard::Card(seven_of_hearts.clone()),
+                    FirstPlayer::LeftOfDealer,
+                ))
+                .build();
+
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                RuleSetError::OpeningCardNotCentre(seven_of_hearts).to_string()
+            );
+        }
+
+        #[test]
+        fn first_card_centre_accepts_any_opening_card() {
+            let output = RuleSet::builder()
+                .centre(CentreRule::FirstCard)
+                .opening(OpeningRule::new(
+                    OpeningCard::Card(Card {
+                        suit: SuitEnum::Club,
+                        number: NumberEnum::Jack,
+                    }),
+                    FirstPlayer::HolderOfOpeningCard,
+                ))
+                .build();
+
+            assert!(output.is_ok());
+        }
+
+        #[test]
+        fn default_opening_rule_is_any_card_left_of_dealer() {
+            let rule = OpeningRule::default();
+
+            assert_eq!(rule.opening_card, OpeningCard::Any);
+            assert_eq!(rule.first_player, FirstPlayer::LeftOfDealer);
+        }
+    }
+}
+
+mod stack {
+    use crate::card_and_enums::{Card, NumberEnum, SuitEnum};
+    use crate::rule_set::{CentreRule, RuleSet, SequenceRule};
+    use thiserror::Error;
+
+    #[derive(Debug, Clone)]
+    pub struct Stack {
+        pub suit: SuitEnum,
+        up_card: Option<Card>,
+        down_card: Option<Card>,
+        /// The number the stack starts from, or `None` until the first card of a Domino game
+        /// has been played.
+        centre: Option<NumberEnum>,
+        sequence: SequenceRule,
+        /// Whether each number, indexed by rank, is in this suit of the deck.
+        in_deck: [bool; 14],
+        /// How many copies of each card this row takes, for games with several decks.
+        copies: u8,
+        /// Copies played so far of each number, indexed by rank.
+        placed: [u8; 14],
+        /// Wild cards on the row, with the number each stands in for.
+        wilds: Vec<(NumberEnum, Card)>,
+    }
+
+    /// What happened when a card was played on a stack.
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct Played {
+        /// The wild card the card took the place of, if any.
+        pub replaced: Option<Card>,
+        /// The card was the stack's centre.
+        pub opened: bool,
+        /// The card finished one direction of the stack.
+        pub completed: bool,
+    }
+
+    #[derive(Debug, Error)]
+    pub enum StackError {
+        #[error("Invalid stack state")]
+        InvalidStackState,
+
+        #[error("Up position contains card lower than the centre card")]
+        InvalidUpStack,
+
+        #[error("Down position contains card higher than the centre card")]
+        InvalidDownStack,
+
+        #[error("Attempted to play on a completed stack")]
+        CompletedStackPlayedOn,
+
+        #[error("Attempted to play a card with an unplayable number")]
+        UnplayableCardNumber,
+
+        #[error("Attempted to change the centre of a stack that has been played on")]
+        CentreAlreadyPlayed,
+    }
+
+    impl Stack {
+        #[allow(clippy::needless_return, clippy::redundant_field_names)]
+        pub fn new(suit: SuitEnum, rules: &RuleSet) -> Stack {
+            let mut in_deck = [false; 14];
+            for number in rules.deck.numbers(suit) {
+                in_deck[number.rank() as usize] = true;
+            }
+            return Stack {
+                suit: suit,
+                up_card: None,
+                down_card: None,
+                centre: match rules.centre {
+                    CentreRule::Fixed(centre) => Some(centre),
+                    CentreRule::FirstCard => None,
+                },
+                sequence: rules.sequence,
+                in_deck: in_deck,
+                copies: rules.copies_per_row(),
+                placed: [0; 14],
+                wilds: Vec::new(),
+            };
+        }
+
+        /// Returns true if nothing has been played on this stack yet.
+        pub fn is_empty(&self) -> bool {
+            self.up_card.is_none() && self.down_card.is_none()
+        }
+
+        /// Returns the number the stack starts from, if it has been chosen.
+        pub fn centre(&self) -> Option<NumberEnum> {
+            self.centre
+        }
+
+        /// Sets the number an empty stack starts from.
+        pub fn set_centre(&mut self, centre: NumberEnum) -> Result<(), StackError> {
+            if !self.is_empty() {
+                return Err(StackError::CentreAlreadyPlayed);
+            }
+            self.centre = Some(centre);
+            Ok(())
+        }
+
+        #[allow(clippy::needless_return)]
+        pub fn get_playable_cards(&self) -> Result<Option<Vec<Card>>, StackError> {
+            match (&self.up_card, &self.down_card) {
+                (None, None) => {
+                    // if nothing has been played, then only the centre card is playable, or any
+                    // card if the centre has not been chosen yet
+                    match self.centre {
+                        Some(centre) => {
+                            return Ok(Some(vec![Card {
+                                suit: self.suit,
+                                number: centre,
+                            }]))
+                        }
+                        None => {
+                            return Ok(Some(
+                                NumberEnum::iterator()
+                                    .filter(|number| self.in_deck[number.rank() as usize])
+                                    .map(|number| Card {
+                                        suit: self.suit,
+                                        number,
+                                    })
+                                    .collect(),
+                            ))
+                        }
+                    }
+                }
+                (Some(up_card), Some(down_card)) => {
+                    // if at least the centre card has been played, then return the next playable card on each stack,
+                    // or None if the direction is complete
+                    let centre = match self.centre {
+                        Some(centre) => centre,
+                        None => return Err(StackError::InvalidStackState),
+                    };
+                    if self.sequence == SequenceRule::Linear {
+                        // a wrapped row can run past the centre in either direction
+                        if up_card.number.rank() < centre.rank() {
+                            return Err(StackError::InvalidUpStack);
+                        }
+                        if down_card.number.rank() > centre.rank() {
+                            return Err(StackError::InvalidDownStack);
+                        }
+                    }
+                    let playable_up: Option<NumberEnum> = self.next_up(up_card.number);
+                    let mut playable_down: Option<NumberEnum> = self.next_down(down_card.number);
+                    if playable_down == playable_up {
+                        // the last gap in a wrapped row can be filled from either end
+                        playable_down = None;
+                    }
+                    let mut output: Vec<Card> = Vec::new();
+                    for number in playable_up.into_iter().chain(playable_down) {
+                        output.push(Card {
+                            suit: self.suit,
+                            number,
+                        });
+                    }
+                    output.append(&mut self.playable_duplicates());
+                    // the real card can always be laid on a wild standing in for it
+                    for (number, _wild) in self.wilds.iter() {
+                        let card = Card {
+                            suit: self.suit,
+                            number: *number,
+                        };
+                        if !output.contains(&card) {
+                            output.push(card);
+                        }
+                    }
+                    match output.is_empty() {
+                        false => return Ok(Some(output)),
+                        true => return Ok(None),
+                    }
+                }
+                _ => return Err(StackError::InvalidStackState),
+            }
+        }
+
+        /// Returns the cards a wild card could stand in for on this stack: any playable card
+        /// except those already stood in for, once the stack has been opened.
+        pub fn get_wild_placements(&self) -> Result<Vec<Card>, StackError> {
+            if self.is_empty() {
+                return Ok(vec![]);
+            }
+            let playable_cards = self.get_playable_cards()?.unwrap_or_default();
+            Ok(playable_cards
+                .into_iter()
+                .filter(|card| !self.wilds.iter().any(|(number, _)| *number == card.number))
+                .collect())
+        }
+
+        /// Plays `wild` in place of the card numbered `card_number`.
+        pub fn play_wild(
+            &mut self,
+            card_number: NumberEnum,
+            wild: Card,
+        ) -> Result<Played, StackError> {
+            let card = Card {
+                suit: self.suit,
+                number: card_number,
+            };
+            if !self.get_wild_placements()?.contains(&card) {
+                return Err(StackError::UnplayableCardNumber);
+            }
+            let played = self.play_card(card_number)?;
+            self.wilds.push((card_number, wild));
+            Ok(played)
+        }
+
+        /// Plays the card numbered `card_number`, returning what the play did.
+        #[allow(clippy::needless_return)]
+        pub fn play_card(&mut self, card_number: NumberEnum) -> Result<Played, StackError> {
+            let playable_cards = match self.get_playable_cards()? {
+                Some(cards) => cards,
+                None => return Err(StackError::CompletedStackPlayedOn),
+            }; // get playable card(s), if none, then stack is complete
+            let card = Card {
+                suit: self.suit,
+                number: card_number,
+            };
+            if !playable_cards.contains(&card) {
+                // not contained in the playable cards, therefore an unplayable number
+                return Err(StackError::UnplayableCardNumber);
+            }
+            if let Some(position) = self
+                .wilds
+                .iter()
+                .position(|(number, _)| *number == card_number)
+            {
+                // the real card takes the wild card's place
+                return Ok(Played {
+                    replaced: Some(self.wilds.remove(position).1),
+                    ..Played::default()
+                });
+            }
+            let rank = card_number.rank() as usize;
+            if self.placed[rank] > 0 {
+                // a duplicate is laid on its twin, leaving the ends of the row where they are
+                self.placed[rank] += 1;
+                return Ok(Played::default());
+            }
+            let plays_up = match &self.up_card {
+                Some(up_card) => self.next_up(up_card.number) == Some(card_number),
+                None => false,
+            };
+            self.placed[rank] = 1;
+            // the first card played on a stack without a centre becomes its centre
+            let centre = *self.centre.get_or_insert(card_number);
+            let completed = if card_number == centre {
+                // handle the special case for the centre card, which plays on both stacks
+                self.up_card = Some(card.clone());
+                self.down_card = Some(card);
+                false
+            } else if plays_up {
+                // if the card follows the up card, it plays on the up stack
+                self.up_card = Some(card);
+                self.next_up(card_number).is_none()
+            } else {
+                // otherwise it follows the down card, and plays on the downwards stack
+                self.down_card = Some(card);
+                self.next_down(card_number).is_none()
+            };
+            return Ok(Played {
+                replaced: None,
+                opened: card_number == centre,
+                completed,
+            });
+        }
+
+        /// Returns the number in the deck that plays above `number`, or `None` if the up
+        /// direction is finished.
+        fn next_up(&self, number: NumberEnum) -> Option<NumberEnum> {
+            let above = (number.rank() + 1..=13).find(|rank| self.in_deck[*rank as usize]);
+            let next = match (above, self.sequence) {
+                (Some(rank), _) => rank,
+                (None, SequenceRule::Wraparound) => self.lowest_rank()?,
+                (None, SequenceRule::Linear) => return None,
+            };
+            self.unplaced(NumberEnum::from_rank(next)?)
+        }
+
+        /// Appends bytes describing what has been played on the stack to `key`.
+        pub fn extend_key(&self, key: &mut Vec<u8>) {
+            for end in [&self.up_card, &self.down_card] {
+                key.push(end.as_ref().map_or(u8::MAX, |card| card.number.rank()));
+            }
+            key.push(self.centre.map_or(u8::MAX, |centre| centre.rank()));
+            key.extend_from_slice(&self.placed);
+            let mut wilds: Vec<(u8, u8)> = self
+                .wilds
+                .iter()
+                .map(|(number, wild)| (number.rank(), wild.index()))
+                .collect();
+            wilds.sort();
+            key.push(wilds.len() as u8);
+            for (number, wild) in wilds {
+                key.push(number);
+                key.push(wild);
+            }
+        }
+
+        /// Returns true if `number` plays directly on from `previous` once `previous` is down,
+        /// so the two can be laid in the same run.
+        pub fn continues(&self, previous: NumberEnum, number: NumberEnum) -> bool {
+            self.placed[previous.rank() as usize] > 0
+                && (self.next_up(previous) == Some(number)
+                    || self.next_down(previous) == Some(number))
+        }
+
+        /// Returns the number in the deck that plays below `number`, or `None` if the down
+        /// direction is finished.
+        fn next_down(&self, number: NumberEnum) -> Option<NumberEnum> {
+            let below = (1..number.rank())
+                .rev()
+                .find(|rank| self.in_deck[*rank as usize]);
+            let next = match (below, self.sequence) {
+                (Some(rank), _) => rank,
+                (None, SequenceRule::Wraparound) => self.highest_rank()?,
+                (None, SequenceRule::Linear) => return None,
+            };
+            self.unplaced(NumberEnum::from_rank(next)?)
+        }
+
+        fn lowest_rank(&self) -> Option<u8> {
+            (1..=13).find(|rank| self.in_deck[*rank as usize])
+        }
+
+        fn highest_rank(&self) -> Option<u8> {
+            (1..=13).rev().find(|rank| self.in_deck[*rank as usize])
+        }
+
+        /// Returns `number` unless it is already in the row, which happens once both ends of
+        /// a wrapped row meet.
+        fn unplaced(&self, number: NumberEnum) -> Option<NumberEnum> {
+            match self.placed[number.rank() as usize] {
+                0 => Some(number),
+                _ => None,
+            }
+        }
+
+        /// Returns the duplicates that can still be laid on cards already in the row.
+        fn playable_duplicates(&self) -> Vec<Card> {
+            if self.copies <= 1 {
+                return vec![];
+            }
+            (1..=13)
+                .filter(|rank| {
+                    self.placed[*rank as usize] > 0 && self.placed[*rank as usize] < self.copies
+                })
+                .filter_map(NumberEnum::from_rank)
+                .map(|number| Card {
+                    suit: self.suit,
+                    number,
+                })
+                .collect()
+        }
+
+        #[cfg(test)]
+        #[allow(clippy::redundant_field_names)]
+        pub fn from(
+            suit: SuitEnum,
+            up_card: Option<Card>,
+            down_card: Option<Card>,
+        ) -> Result<Stack, StackError> {
+            let output = Stack {
+                suit: suit,
+                up_card: up_card,
+                down_card: down_card,
+                centre: Some(NumberEnum::Seven),
+                sequence: SequenceRule::Linear,
+                in_deck: [true; 14],
+                copies: 1,
+                placed: [0; 14],
+                wilds: Vec::new(),
+            };
+            match output.get_playable_cards() {
+                Ok(_) => Ok(output),
+                Err(e) => Err(e),
+            }
+        }
+
+        #[cfg(test)]
+        #[allow(clippy::needless_return, clippy::redundant_field_names)]
+        pub fn get_completed_stack(suit: SuitEnum) -> Stack {
+            return Stack {
+                suit: suit,
+                up_card: Some(Card {
+                    suit: suit,
+                    number: NumberEnum::King,
+                }),
+                down_card: Some(Card {
+                    suit: suit,
+                    number: NumberEnum::Ace,
+                }),
+                centre: Some(NumberEnum::Seven),
+                sequence: SequenceRule::Linear,
+                in_deck: [true; 14],
+                copies: 1,
+                placed: [0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
+                wilds: Vec::new(),
+            };
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+
+        use super::*;
+
+        #[test]
+        fn initialization() {
+            let stack = Stack::new(SuitEnum::Spade, &RuleSet::default());
+            assert!(stack.up_card.is_none());
+            assert!(stack.down_card.is_none());
+            assert_eq!(stack.suit, SuitEnum::Spade);
+        }
+
+        mod test_get_playable_cards {
+
+            use super::*;
+
+            #[test]
+            fn only_seven_plays_on_new_stack() {
+                let stack = Stack::new(SuitEnum::Club, &RuleSet::default());
+                let playable_cards = stack
+                    .get_playable_cards()
+                    .expect("Error in Stack::get_playable_cards");
+                assert!(playable_cards.is_some());
+
+                let playable_cards = playable_cards.unwrap();
+                assert_eq!(playable_cards.len(), 1);
+                assert!(playable_cards.contains(&Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Seven
+                }));
+            }
+
+            #[test]
+            fn only_centre_card_plays_on_new_stack() {
+                let rules = RuleSet::builder()
+                    .centre(CentreRule::Fixed(NumberEnum::Eight))
+                    .build()
+                    .unwrap();
+                let stack = Stack::new(SuitEnum::Club, &rules);
+                let playable_cards = stack
+                    .get_playable_cards()
+                    .expect("Error in Stack::get_playable_cards")
+                    .unwrap();
+
+                assert_eq!(
+                    playable_cards,
+                    vec![Card {
+                        suit: SuitEnum::Club,
+                        number: NumberEnum::Eight
+                    }]
+                );
+            }
+
+            #[test]
+            fn eight_and_six_play_on_seven() {
+                let mut stack = Stack::new(SuitEnum::Diamond, &RuleSet::default());
+                let seven = Card {
+                    suit: SuitEnum::Diamond,
+                    number: NumberEnum::Seven,
+                };
+                stack.up_card = Some(seven.clone());
+                stack.down_card = Some(seven);
+
+                let playable_cards = stack
+                    .get_playable_cards()
+                    .expect("Error in Stack::get_playable_cards")
+                    .unwrap();
+
+                assert_eq!(playable_cards.len(), 2);
+                assert!(playable_cards.contains(&Card {
+                    suit: SuitEnum::Diamond,
+                    number: NumberEnum::Six
+                }));
+                assert!(playable_cards.contains(&Card {
+                    suit: SuitEnum::Diamond,
+                    number: NumberEnum::Eight
+                }));
+            }
+
+            #[test]
+            fn finished_up_stack_does_not_return_playable() {
+                let mut stack = Stack::new(SuitEnum::Heart, &RuleSet::default());
+                let seven = Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::Seven,
+                };
+                let king = Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::King,
+                };
+                stack.up_card = Some(king);
+                stack.down_card = Some(seven);
+
+                let playable_cards = stack
+                    .get_playable_cards()
+                    .expect("Error in Stack::get_playable_cards")
+                    .unwrap();
+
+                assert_eq!(playable_cards.len(), 1);
+                assert!(playable_cards.contains(&Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::Six
+                }));
+            }
+
+            #[test]
+            fn finished_down_stack_does_not_return_playable() {
+                let mut stack = Stack::new(SuitEnum::Heart, &RuleSet::default());
+                let seven = Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::Seven,
+                };
+                let ace = Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::Ace,
+                };
+                stack.up_card = Some(seven);
+                stack.down_card = Some(ace);
+
+                let playable_cards = stack
+                    .get_playable_cards()
+                    .expect("Error in Stack::get_playable_cards")
+                    .unwrap();
+
+                assert_eq!(playable_cards.len(), 1);
+                assert!(playable_cards.contains(&Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::Eight
+                }));
+            }
+
+            #[test]
+            fn completed_stack_does_not_return_playable() {
+                let mut stack = Stack::new(SuitEnum::Heart, &RuleSet::default());
+                let king = Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::King,
+                };
+                let ace = Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::Ace,
+                };
+                stack.up_card = Some(king);
+                stack.down_card = Some(ace);
+
+                let playable_cards = stack
+                    .get_playable_cards()
+                    .expect("Error in Stack::get_playable_cards");
+
+                assert!(playable_cards.is_none());
+            }
+
+            #[test]
+            fn invalid_stack_state() {
+                let mut stack = Stack::new(SuitEnum::Heart, &RuleSet::default());
+                let ace = Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::Ace,
+                };
+                stack.down_card = Some(ace);
+
+                let output = stack.get_playable_cards();
+
+                assert!(output.is_err());
+                assert_eq!(
+                    output.unwrap_err().to_string(),
+                    StackError::InvalidStackState.to_string()
+                )
+            }
+
+            #[test]
+            fn invalid_up_state() {
+                let mut stack = Stack::new(SuitEnum::Heart, &RuleSet::default());
+                let ace = Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::Ace,
+                };
+                stack.up_card = Some(ace.clone());
+                stack.down_card = Some(ace);
+
+                let output = stack.get_playable_cards();
+
+                assert!(output.is_err());
+                assert_eq!(
+                    output.unwrap_err().to_string(),
+                    StackError::InvalidUpStack.to_string()
+                )
+            }
+
+            #[test]
+            fn from_rejects_invalid_stack_state() {
+                let output = Stack::from(
+                    SuitEnum::Heart,
+                    None,
+                    Some(Card {
+                        suit: SuitEnum::Heart,
+                        number: NumberEnum::Six,
+                    }),
+                );
+
+                assert!(output.is_err());
+                assert_eq!(
+                    output.unwrap_err().to_string(),
+                    StackError::InvalidStackState.to_string()
+                )
+            }
+
+            #[test]
+            fn invalid_down_state() {
+                let mut stack = Stack::new(SuitEnum::Heart, &RuleSet::default());
+                let king = Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::King,
+                };
+                stack.down_card = Some(king.clone());
+                stack.up_card = Some(king);
+
+                let output = stack.get_playable_cards();
+
+                assert!(output.is_err());
+                assert_eq!(
+                    output.unwrap_err().to_string(),
+                    StackError::InvalidDownStack.to_string()
+                )
+            }
+        }
+
+        mod test_play_card {
+
+            use super::*;
+            use crate::rule_set::{DeckSpec, DuplicateRows};
+
+            #[test]
+            fn seven_plays_on_new_stack() {
+                let mut stack = Stack::new(SuitEnum::Club, &RuleSet::default());
+                let seven = Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Seven,
+                };
+
+                let output = stack.play_card(NumberEnum::Seven);
+
+                assert!(output.is_ok());
+                assert_eq!(stack.up_card.unwrap(), seven);
+                assert_eq!(stack.down_card.unwrap(), seven);
+            }
+
+            #[test]
+            fn play_reports_opening_and_completion() {
+                let rules = RuleSet::builder().deck(DeckSpec::Piquet).build().unwrap();
+                let mut stack = Stack::new(SuitEnum::Club, &rules);
+
+                let opened = stack.play_card(NumberEnum::Seven).unwrap();
+                assert!(opened.opened);
+                assert!(!opened.completed);
+
+                let completed = stack.play_card(NumberEnum::Ace).unwrap();
+                assert!(!completed.opened);
+                assert!(completed.completed);
+                assert!(!stack.play_card(NumberEnum::Eight).unwrap().completed);
+            }
+
+            #[test]
+            fn centre_card_splits_up_and_down_stacks() {
+                let rules = RuleSet::builder()
+                    .centre(CentreRule::Fixed(NumberEnum::Eight))
+                    .build()
+                    .unwrap();
+                let mut stack = Stack::new(SuitEnum::Club, &rules);
+
+                stack.play_card(NumberEnum::Eight).unwrap();
+                stack.play_card(NumberEnum::Seven).unwrap();
+                stack.play_card(NumberEnum::Nine).unwrap();
+
+                assert_eq!(stack.up_card.unwrap().number, NumberEnum::Nine);
+                assert_eq!(stack.down_card.unwrap().number, NumberEnum::Seven);
+            }
+
+            #[test]
+            fn duplicates_play_on_shared_row() {
+                let rules = RuleSet::builder()
+                    .decks(2, DuplicateRows::Shared)
+                    .build()
+                    .unwrap();
+                let mut stack = Stack::new(SuitEnum::Club, &rules);
+
+                stack.play_card(NumberEnum::Seven).unwrap();
+                stack.play_card(NumberEnum::Eight).unwrap();
+                let playable_cards = stack.get_playable_cards().unwrap().unwrap();
+                assert_eq!(playable_cards.len(), 4);
+                for number in [
+                    NumberEnum::Six,
+                    NumberEnum::Seven,
+                    NumberEnum::Eight,
+                    NumberEnum::Nine,
+                ] {
+                    assert!(playable_cards.contains(&Card {
+                        suit: SuitEnum::Club,
+                        number
+                    }));
+                }
+
+                stack.play_card(NumberEnum::Seven).unwrap();
+                assert_eq!(stack.up_card.clone().unwrap().number, NumberEnum::Eight);
+                assert_eq!(stack.down_card.clone().unwrap().number, NumberEnum::Seven);
+                let output = stack.play_card(NumberEnum::Seven);
+                assert!(output.is_err());
+                assert_eq!(
+                    output.unwrap_err().to_string(),
+                    StackError::UnplayableCardNumber.to_string()
+                );
+            }
+
+            #[test]
+            fn first_card_sets_centre_of_open_stack() {
+                let rules = RuleSet::builder()
+                    .centre(CentreRule::FirstCard)
+                    .build()
+                    .unwrap();
+                let mut stack = Stack::new(SuitEnum::Club, &rules);
+                assert_eq!(stack.get_playable_cards().unwrap().unwrap().len(), 13);
+
+                stack.play_card(NumberEnum::Jack).unwrap();
+
+                assert_eq!(stack.centre, Some(NumberEnum::Jack));
+                let playable_cards = stack.get_playable_cards().unwrap().unwrap();
+                assert_eq!(playable_cards.len(), 2);
+                assert!(playable_cards.contains(&Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Ten
+                }));
+                assert!(playable_cards.contains(&Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Queen
+                }));
+            }
+
+            #[test]
+            fn centre_cannot_change_after_play() {
+                let mut stack = Stack::new(SuitEnum::Club, &RuleSet::default());
+                stack.play_card(NumberEnum::Seven).unwrap();
+
+                let output = stack.set_centre(NumberEnum::Eight);
+
+                assert!(output.is_err());
+                assert_eq!(
+                    output.unwrap_err().to_string(),
+                    StackError::CentreAlreadyPlayed.to_string()
+                );
+            }
+
+            fn wraparound_rules() -> RuleSet {
+                RuleSet::builder()
+                    .sequence(SequenceRule::Wraparound)
+                    .build()
+                    .unwrap()
+            }
+
+            #[test]
+            fn wild_stands_in_until_real_card_replaces_it() {
+                let mut stack = Stack::new(SuitEnum::Club, &RuleSet::default());
+                assert!(stack.get_wild_placements().unwrap().is_empty());
+                stack.play_card(NumberEnum::Seven).unwrap();
+
+                stack.play_wild(NumberEnum::Eight, Card::joker()).unwrap();
+
+                assert_eq!(stack.up_card.clone().unwrap().number, NumberEnum::Eight);
+                let placements = stack.get_wild_placements().unwrap();
+                assert_eq!(placements.len(), 2);
+                assert!(!placements.contains(&Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Eight
+                }));
+                assert!(stack
+                    .get_playable_cards()
+                    .unwrap()
+                    .unwrap()
+                    .contains(&Card {
+                        suit: SuitEnum::Club,
+                        number: NumberEnum::Eight
+                    }));
+
+                let played = stack.play_card(NumberEnum::Eight).unwrap();
+
+                assert_eq!(played.replaced, Some(Card::joker()));
+                assert!(!stack
+                    .get_playable_cards()
+                    .unwrap()
+                    .unwrap()
+                    .contains(&Card {
+                        suit: SuitEnum::Club,
+                        number: NumberEnum::Eight
+                    }));
+            }
+
+            #[test]
+            fn wild_cannot_open_a_stack() {
+                let mut stack = Stack::new(SuitEnum::Club, &RuleSet::default());
+
+                let output = stack.play_wild(NumberEnum::Seven, Card::joker());
+
+                assert!(output.is_err());
+                assert!(stack.is_empty());
+            }
+
+            #[test]
+            fn piquet_stack_completes_at_its_own_numbers() {
+                let rules = RuleSet::builder().deck(DeckSpec::Piquet).build().unwrap();
+                let mut stack = Stack::new(SuitEnum::Diamond, &rules);
+                stack.play_card(NumberEnum::Seven).unwrap();
+
+                // the Ace is the next number down from the seven in a piquet deck
+                assert_eq!(
+                    stack.get_playable_cards().unwrap().unwrap(),
+                    vec![
+                        Card {
+                            suit: SuitEnum::Diamond,
+                            number: NumberEnum::Eight
+                        },
+                        Card {
+                            suit: SuitEnum::Diamond,
+                            number: NumberEnum::Ace
+                        }
+                    ]
+                );
+
+                for number in [
+                    NumberEnum::Ace,
+                    NumberEnum::Eight,
+                    NumberEnum::Nine,
+                    NumberEnum::Ten,
+                    NumberEnum::Jack,
+                    NumberEnum::Queen,
+                    NumberEnum::King,
+                ] {
+                    stack.play_card(number).unwrap();
+                }
+                assert!(stack.get_playable_cards().unwrap().is_none());
+            }
+
+            #[test]
+            fn spanish_stack_skips_missing_numbers() {
+                let rules = RuleSet::builder().deck(DeckSpec::Spanish).build().unwrap();
+                let mut stack = Stack::new(SuitEnum::Club, &rules);
+                stack.play_card(NumberEnum::Seven).unwrap();
+
+                stack.play_card(NumberEnum::Jack).unwrap();
+
+                assert_eq!(stack.up_card.unwrap().number, NumberEnum::Jack);
+            }
+
+            #[test]
+            fn ace_follows_king_when_wrapping() {
+                let mut stack = Stack::new(SuitEnum::Spade, &wraparound_rules());
+                for rank in 7..=13 {
+                    stack
+                        .play_card(NumberEnum::from_rank(rank).unwrap())
+                        .unwrap();
+                }
+
+                let playable_cards = stack.get_playable_cards().unwrap().unwrap();
+                assert!(playable_cards.contains(&Card {
+                    suit: SuitEnum::Spade,
+                    number: NumberEnum::Ace
+                }));
+
+                stack.play_card(NumberEnum::Ace).unwrap();
+                assert_eq!(stack.up_card.clone().unwrap().number, NumberEnum::Ace);
+                assert_eq!(stack.down_card.clone().unwrap().number, NumberEnum::Seven);
+            }
+
+            #[test]
+            fn king_follows_ace_going_down_when_wrapping() {
+                let mut stack = Stack::new(SuitEnum::Spade, &wraparound_rules());
+                for rank in (1..=7).rev() {
+                    stack
+                        .play_card(NumberEnum::from_rank(rank).unwrap())
+                        .unwrap();
+                }
+
+                stack.play_card(NumberEnum::King).unwrap();
+
+                assert_eq!(stack.up_card.unwrap().number, NumberEnum::Seven);
+                assert_eq!(stack.down_card.unwrap().number, NumberEnum::King);
+            }
+
+            #[test]
+            fn wrapped_stack_completes_when_ends_meet() {
+                let mut stack = Stack::new(SuitEnum::Heart, &wraparound_rules());
+                // up from the seven, round through the Ace, to the five
+                for rank in [7, 8, 9, 10, 11, 12, 13, 1, 2, 3, 4, 5] {
+                    stack
+                        .play_card(NumberEnum::from_rank(rank).unwrap())
+                        .unwrap();
+                }
+                assert_eq!(
+                    stack.get_playable_cards().unwrap().unwrap(),
+                    vec![Card {
+                        suit: SuitEnum::Heart,
+                        number: NumberEnum::Six
+                    }]
+                );
+
+                stack.play_card(NumberEnum::Six).unwrap();
+
+                assert!(stack.get_playable_cards().unwrap().is_none());
+                let output = stack.play_card(NumberEnum::Seven);
+                assert!(output.is_err());
+                assert_eq!(
+                    output.unwrap_err().to_string(),
+                    StackError::CompletedStackPlayedOn.to_string()
+                );
+            }
+
+            #[test]
+            fn eight_plays_on_up_stack_with_seven() {
+                let mut stack = Stack::new(SuitEnum::Club, &RuleSet::default());
+                let seven = Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Seven,
+                };
+                let eight = Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Eight,
+                };
+                stack.up_card = Some(seven.clone());
+                stack.down_card = Some(seven.clone());
+
+                let output = stack.play_card(NumberEnum::Eight);
+
+                assert!(output.is_ok());
+                assert_eq!(stack.up_card.unwrap(), eight);
+                assert_eq!(stack.down_card.unwrap(), seven);
+            }
+
+            #[test]
+            fn six_plays_on_down_stack_with_seven() {
+                let mut stack = Stack::new(SuitEnum::Club, &RuleSet::default());
+                let seven = Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Seven,
+                };
+                let six = Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Six,
+                };
+                stack.up_card = Some(seven.clone());
+                stack.down_card = Some(seven.clone());
+
+                let output = stack.play_card(NumberEnum::Six);
+
+                assert!(output.is_ok());
+                assert_eq!(stack.up_card.unwrap(), seven);
+                assert_eq!(stack.down_card.unwrap(), six);
+            }
+
+            #[test]
+            fn unplayable_card_returns_unplayablecard() {
+                let mut stack = Stack::new(SuitEnum::Club, &RuleSet::default());
+                let seven = Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Seven,
+                };
+                stack.up_card = Some(seven.clone());
+                stack.down_card = Some(seven.clone());
+
+                let output = stack.play_card(NumberEnum::King);
+
+                assert!(output.is_err());
+                assert_eq!(
+                    output.unwrap_err().to_string(),
+                    StackError::UnplayableCardNumber.to_string()
+                );
+                assert_eq!(stack.up_card.unwrap(), seven);
+                assert_eq!(stack.down_card.unwrap(), seven);
+            }
+
+            #[test]
+            fn playing_on_completed_stack_returns_completedstackplayedon() {
+                let mut stack = Stack::new(SuitEnum::Club, &RuleSet::default());
+                let ace = Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Ace,
+                };
+                let king = Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::King,
+                };
+                stack.up_card = Some(king.clone());
+                stack.down_card = Some(ace.clone());
+
+                let output = stack.play_card(NumberEnum::King);
+
+                assert!(output.is_err());
+                assert_eq!(
+                    output.unwrap_err().to_string(),
+                    StackError::CompletedStackPlayedOn.to_string()
+                );
+                assert_eq!(stack.up_card.unwrap(), king);
+                assert_eq!(stack.down_card.unwrap(), ace);
+            }
+        }
+    }
+}
+
+mod game_board {
+
+    use crate::card_and_enums::{Card, NumberEnum, SuitEnum};
+    use crate::rule_set::{BuildingRule, OpeningCard, RuleSet};
+    use crate::stack::{Played, Stack, StackError};
+    use thiserror::Error;
+
+    #[derive(Debug, Clone)]
+    pub struct GameBoard {
+        /// Every row on the table, grouped by suit. With several decks a suit may have more
+        /// than one row.
+        stacks: Vec<Stack>,
+        opening_card: OpeningCard,
+        building: BuildingRule,
+    }
+
+    #[derive(Debug, Error)]
+    pub enum GameBoardError {
+        #[error("'{0}' error in {1} stack")]
+        StackError(StackError, String),
+
+        #[error("Opening card {0:?} cannot be played on an empty board")]
+        InvalidOpeningCard(Card),
+
+        #[error("The game must be opened with {0:?}")]
+        OpeningCardRequired(Card),
+
+        #[error("No {0} row can be built on yet")]
+        BuildingNotAllowed(String),
+
+        #[error("{0:?} does not follow on from the card before it in the run")]
+        BrokenRun(Card),
+    }
+
+    fn suit_name(suit: SuitEnum) -> String {
+        match suit {
+            SuitEnum::Spade => "Spades".to_string(),
+            SuitEnum::Club => "Clubs".to_string(),
+            SuitEnum::Heart => "Hearts".to_string(),
+            SuitEnum::Diamond => "Diamonds".to_string(),
+        }
+    }
+
+    impl GameBoard {
+        /// Creates an empty board for `rules`. If the rules name an opening card, only that
+        /// card may be played first.
+        #[allow(clippy::needless_return)]
+        pub fn new(rules: &RuleSet) -> Result<GameBoard, GameBoardError> {
+            let mut stacks: Vec<Stack> = Vec::new();
+            for suit in SuitEnum::iterator() {
+                if rules.deck.numbers(suit).is_empty() {
+                    continue;
+                }
+                for _row in 0..rules.rows_per_suit() {
+                    stacks.push(Stack::new(suit, rules));
+                }
+            }
+            let mut output = GameBoard {
+                stacks,
+                opening_card: OpeningCard::Any,
+                building: rules.building,
+            };
+            if let OpeningCard::Card(ref card) = rules.opening.opening_card {
+                let playable = output.get_playable_cards()?.unwrap_or_default();
+                if !playable.contains(card) {
+                    return Err(GameBoardError::InvalidOpeningCard(card.clone()));
+                }
+            }
+            output.opening_card = rules.opening.opening_card.clone();
+            return Ok(output);
+        }
+
+        /// Returns true if no card has been played yet.
+        pub fn is_empty(&self) -> bool {
+            self.stacks.iter().all(|stack| stack.is_empty())
+        }
+
+        /// Replaces the first row of each given stack's suit.
+        #[cfg(test)]
+        #[allow(clippy::needless_return)]
+        pub fn from(stacks: Vec<Stack>) -> Result<GameBoard, GameBoardError> {
+            let mut output = GameBoard::new(&RuleSet::default())?;
+            for st in stacks {
+                if let Some(position) = output.stacks.iter().position(|row| row.suit == st.suit) {
+                    output.stacks[position] = st;
+                }
+            }
+            match output.get_playable_cards() {
+                Ok(_) => return Ok(output),
+                Err(e) => return Err(e),
+            }
+        }
+
+        /// Returns every card that can be played, listing identical cards playable on more
+        /// than one row only once.
+        #[allow(clippy::needless_return)]
+        pub fn get_playable_cards(&self) -> Result<Option<Vec<Card>>, GameBoardError> {
+            if let OpeningCard::Card(ref card) = self.opening_card {
+                if self.is_empty() {
+                    return Ok(Some(vec![card.clone()]));
+                }
+            }
+            let mut output: Vec<Card> = Vec::new();
+            for (index, stack) in self.stacks.iter().enumerate() {
+                if !stack.is_empty() && !self.may_build_on(index) {
+                    continue;
+                }
+                let playable: Vec<Card> = match stack.get_playable_cards() {
+                    Ok(vec_cards) => vec_cards.unwrap_or_default(),
+                    Err(e) => return Err(GameBoardError::StackError(e, suit_name(stack.suit))),
+                };
+                for card in playable {
+                    if !output.contains(&card) {
+                        output.push(card);
+                    }
+                }
+            }
+            if !output.is_empty() {
+                return Ok(Some(output));
+            }
+            return Ok(None);
+        }
+
+        /// Returns the number rows start from, or `None` if the first card of a Domino game
+        /// has not been played yet.
+        pub fn centre(&self) -> Option<NumberEnum> {
+            self.stacks.first().and_then(|stack| stack.centre())
+        }
+
+        /// Returns every card a wild card could stand in for, listing each card only once.
+        pub fn get_wild_placements(&self) -> Result<Vec<Card>, GameBoardError> {
+            let mut output: Vec<Card> = Vec::new();
+            for (index, stack) in self.stacks.iter().enumerate() {
+                if !self.may_build_on(index) {
+                    continue;
+                }
+                let placements = match stack.get_wild_placements() {
+                    Ok(placements) => placements,
+                    Err(e) => return Err(GameBoardError::StackError(e, suit_name(stack.suit))),
+                };
+                for card in placements {
+                    if !output.contains(&card) {
+                        output.push(card);
+                    }
+                }
+            }
+            Ok(output)
+        }
+
+        /// Plays `wild` in place of `card`, on the first row of its suit that accepts it.
+        pub fn play_wild(&mut self, wild: Card, card: Card) -> Result<Played, GameBoardError> {
+            self.play_on_first_row(card.suit, |stack| {
+                stack.play_wild(card.number, wild.clone())
+            })
+        }
+
+        /// Plays `card` on the first row of its suit that accepts it, returning what the play
+        /// did. A card can only extend the same end of any of its suit's rows, so which row is
+        /// used does not change what can be played later; wraparound rows, which could differ,
+        /// are never kept separate. In a Domino game the first card sets the centre of every
+        /// row.
+        pub fn play_card(&mut self, card: Card) -> Result<Played, GameBoardError> {
+            if let OpeningCard::Card(ref opening_card) = self.opening_card {
+                if self.is_empty() && *opening_card != card {
+                    return Err(GameBoardError::OpeningCardRequired(opening_card.clone()));
+                }
+            }
+            if self.centre().is_none() {
+                // the centre is only kept if the card can then be played on it
+                let mut board = self.clone();
+                for stack in board.stacks.iter_mut() {
+                    if let Err(e) = stack.set_centre(card.number) {
+                        return Err(GameBoardError::StackError(e, suit_name(stack.suit)));
+                    }
+                }
+                let played =
+                    board.play_on_first_row(card.suit, |stack| stack.play_card(card.number))?;
+                *self = board;
+                return Ok(played);
+            }
+            self.play_on_first_row(card.suit, |stack| stack.play_card(card.number))
+        }
+
+        /// Plays `cards` in order as one move, each following on from the card before it in
+        /// the same suit. Either the whole run is played or, if any card of it cannot be,
+        /// none of it is. Returns what each card of the run did.
+        pub fn play_run(&mut self, cards: &[Card]) -> Result<Vec<Played>, GameBoardError> {
+            let mut board = self.clone();
+            let mut output: Vec<Played> = Vec::new();
+            for (index, card) in cards.iter().enumerate() {
+                if index > 0 && !board.continues_run(&cards[index - 1], card) {
+                    return Err(GameBoardError::BrokenRun(card.clone()));
+                }
+                output.push(board.play_card(card.clone())?);
+            }
+            *self = board;
+            Ok(output)
+        }
+
+        /// Appends bytes describing every row to `key`.
+        pub fn extend_key(&self, key: &mut Vec<u8>) {
+            for stack in self.stacks.iter() {
+                stack.extend_key(key);
+            }
+        }
+
+        /// Returns true if `card` plays directly on from `previous` on one of its suit's rows.
+        pub fn continues_run(&self, previous: &Card, card: &Card) -> bool {
+            previous.suit == card.suit
+                && self.stacks.iter().any(|stack| {
+                    stack.suit == card.suit && stack.continues(previous.number, card.number)
+                })
+        }
+
+        /// Returns true if cards other than the centre card can be played on the row at
+        /// `index`.
+        fn may_build_on(&self, index: usize) -> bool {
+            match self.building {
+                BuildingRule::OwnCentre => true,
+                BuildingRule::AllCentres => self.stacks.iter().all(|stack| !stack.is_empty()),
+                BuildingRule::RowAbove => index == 0 || !self.stacks[index - 1].is_empty(),
+            }
+        }
+
+        /// Applies `play` to the first row of `suit` it succeeds on, leaving rows it fails on
+        /// unchanged. Rows that cannot be built on yet are only tried if they are empty.
+        fn play_on_first_row<T>(
+            &mut self,
+            suit: SuitEnum,
+            play: impl Fn(&mut Stack) -> Result<T, StackError>,
+        ) -> Result<T, GameBoardError> {
+            let open_for_play: Vec<bool> = (0..self.stacks.len())
+                .map(|index| self.stacks[index].is_empty() || self.may_build_on(index))
+                .collect();
+            let mut blocked = false;
+            let mut first_error: Option<StackError> = None;
+            for (index, stack) in self.stacks.iter_mut().enumerate() {
+                if stack.suit != suit {
+                    continue;
+                }
+                if !open_for_play[index] {
+                    blocked = true;
+                    continue;
+                }
+                let mut attempt = stack.clone();
+                match play(&mut attempt) {
+                    Ok(output) => {
+                        *stack = attempt;
+                        return Ok(output);
+                    }
+                    Err(e) => {
+                        if first_error.is_none() {
+                            first_error = Some(e);
+                        }
+                    }
+                }
+            }
+            match first_error {
+                Some(e) => Err(GameBoardError::StackError(e, suit_name(suit))),
+                None if blocked => Err(GameBoardError::BuildingNotAllowed(suit_name(suit))),
+                None => Err(GameBoardError::StackError(
+                    StackError::UnplayableCardNumber,
+                    suit_name(suit),
+                )),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::card_and_enums::{NumberEnum, SuitEnum};
+        use crate::rule_set::{BuildingRule, CentreRule, DuplicateRows, FirstPlayer, OpeningRule};
+        use crate::test_helpers::custom_deck;
+
+        #[test]
+        fn initialization() {
+            let game_board = GameBoard::new(&RuleSet::default()).unwrap();
+
+            let suits: Vec<SuitEnum> = game_board.stacks.iter().map(|stack| stack.suit).collect();
+            assert_eq!(suits.len(), 4);
+            assert!(suits.contains(&SuitEnum::Club));
+            assert!(suits.contains(&SuitEnum::Spade));
+            assert!(suits.contains(&SuitEnum::Diamond));
+            assert!(suits.contains(&SuitEnum::Heart));
+        }
+
+        #[test]
+        fn fresh_board_can_play_all_sevens() {
+            let game_board = GameBoard::new(&RuleSet::default()).unwrap();
+
+            let output = game_board.get_playable_cards();
+            assert!(output.is_ok());
+
+            let output = output.unwrap();
+            assert!(output.is_some());
+
+            let output = output.unwrap();
+
+            assert_eq!(output.len(), 4);
+            assert!(output.contains(&Card {
+                suit: SuitEnum::Club,
+                number: NumberEnum::Seven
+            }));
+            assert!(output.contains(&Card {
+                suit: SuitEnum::Spade,
+                number: NumberEnum::Seven
+            }));
+            assert!(output.contains(&Card {
+                suit: SuitEnum::Heart,
+                number: NumberEnum::Seven
+            }));
+            assert!(output.contains(&Card {
+                suit: SuitEnum::Diamond,
+                number: NumberEnum::Seven
+            }));
+        }
+
+        fn board_with_building_rule(building: BuildingRule) -> GameBoard {
+            GameBoard::new(&RuleSet::builder().building(building).build().unwrap()).unwrap()
+        }
+
+        fn seven(suit: SuitEnum) -> Card {
+            Card {
+                suit,
+                number: NumberEnum::Seven,
+            }
+        }
+
+        #[test]
+        fn all_centres_must_be_down_before_building() {
+            let mut game_board = board_with_building_rule(BuildingRule::AllCentres);
+            game_board.play_card(seven(SuitEnum::Club)).unwrap();
+            game_board.play_card(seven(SuitEnum::Heart)).unwrap();
+            game_board.play_card(seven(SuitEnum::Spade)).unwrap();
+
+            let playable = game_board.get_playable_cards().unwrap().unwrap();
+            assert_eq!(playable, vec![seven(SuitEnum::Diamond)]);
+            let output = game_board.play_card(Card {
+                suit: SuitEnum::Club,
+                number: NumberEnum::Eight,
+            });
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                GameBoardError::BuildingNotAllowed("Clubs".to_string()).to_string()
+            );
+
+            game_board.play_card(seven(SuitEnum::Diamond)).unwrap();
+
+            let playable = game_board.get_playable_cards().unwrap().unwrap();
+            assert_eq!(playable.len(), 8);
+        }
+
+        #[test]
+        fn row_above_must_be_open_before_building() {
+            let mut game_board = board_with_building_rule(BuildingRule::RowAbove);
+            game_board.play_card(seven(SuitEnum::Club)).unwrap();
+            game_board.play_card(seven(SuitEnum::Heart)).unwrap();
+
+            // Hearts sit below Clubs, which are open, but Clubs sit below the empty Spades row
+            let playable = game_board.get_playable_cards().unwrap().unwrap();
+            assert_eq!(playable.len(), 4);
+            assert!(playable.contains(&Card {
+                suit: SuitEnum::Heart,
+                number: NumberEnum::Eight
+            }));
+            assert!(!playable.contains(&Card {
+                suit: SuitEnum::Club,
+                number: NumberEnum::Eight
+            }));
+
+            game_board.play_card(seven(SuitEnum::Spade)).unwrap();
+
+            let playable = game_board.get_playable_cards().unwrap().unwrap();
+            assert!(playable.contains(&Card {
+                suit: SuitEnum::Club,
+                number: NumberEnum::Eight
+            }));
+            assert!(playable.contains(&Card {
+                suit: SuitEnum::Spade,
+                number: NumberEnum::Eight
+            }));
+        }
+
+        #[test]
+        fn first_card_sets_centre_for_every_suit() {
+            let rules = RuleSet::builder()
+                .centre(CentreRule::FirstCard)
+                .build()
+                .unwrap();
+            let mut game_board = GameBoard::new(&rules).unwrap();
+            assert_eq!(game_board.centre(), None);
+            assert_eq!(game_board.get_playable_cards().unwrap().unwrap().len(), 52);
+
+            game_board
+                .play_card(Card {
+                    suit: SuitEnum::Heart,
+                    number: NumberEnum::Four,
+                })
+                .unwrap();
+
+            assert_eq!(game_board.centre(), Some(NumberEnum::Four));
+            let playable = game_board.get_playable_cards().unwrap().unwrap();
+            assert_eq!(playable.len(), 5);
+            assert!(playable.contains(&Card {
+                suit: SuitEnum::Spade,
+                number: NumberEnum::Four
+            }));
+            assert!(playable.contains(&Card {
+                suit: SuitEnum::Heart,
+                number: NumberEnum::Three
+            }));
+            assert!(!playable.contains(&Card {
+                suit: SuitEnum::Spade,
+                number: NumberEnum::Seven
+            }));
+        }
+
+        #[test]
+        fn failed_first_card_does_not_set_centre() {
+            let rules = RuleSet::builder()
+                .deck(custom_deck("5H,6H,7H,8H"))
+                .centre(CentreRule::FirstCard)
+                .build()
+                .unwrap();
+            let mut game_board = GameBoard::new(&rules).unwrap();
+
+            // there is no spade row for the seven to go on
+            let output = game_board.play_card(seven(SuitEnum::Spade));
+
+            assert!(output.is_err());
+            assert_eq!(game_board.centre(), None);
+            assert_eq!(game_board.get_playable_cards().unwrap().unwrap().len(), 4);
+        }
+
+        #[test]
+        fn run_is_played_whole_or_not_at_all() {
+            let mut game_board = GameBoard::new(&RuleSet::default()).unwrap();
+            game_board.play_card(seven(SuitEnum::Club)).unwrap();
+            let club = |number: NumberEnum| Card {
+                suit: SuitEnum::Club,
+                number,
+            };
+
+            let output = game_board.play_run(&[club(NumberEnum::Eight), club(NumberEnum::Ten)]);
+            assert!(matches!(output, Err(GameBoardError::BrokenRun(_))));
+            assert!(game_board
+                .get_playable_cards()
+                .unwrap()
+                .unwrap()
+                .contains(&club(NumberEnum::Eight)));
+
+            game_board
+                .play_run(&[
+                    club(NumberEnum::Eight),
+                    club(NumberEnum::Nine),
+                    club(NumberEnum::Ten),
+                ])
+                .unwrap();
+            assert!(game_board
+                .get_playable_cards()
+                .unwrap()
+                .unwrap()
+                .contains(&club(NumberEnum::Jack)));
+        }
+
+        #[test]
+        fn played_seven_plays_eight_and_six() {
+            let mut game_board = GameBoard::new(&RuleSet::default()).unwrap();
+
+            game_board
+                .play_card(Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Seven,
+                })
+                .expect("Failed to play seven");
+
+            let output = game_board.get_playable_cards();
+            assert!(output.is_ok());
+
+            let output = output.unwrap();
+            assert!(output.is_some());
+
+            let output = output.unwrap();
+
+            assert_eq!(output.len(), 5);
+            assert!(output.contains(&Card {
+                suit: SuitEnum::Club,
+                number: NumberEnum::Eight
+            }));
+            assert!(output.contains(&Card {
+                suit: SuitEnum::Club,
+                number: NumberEnum::Six
+            }));
+            assert!(output.contains(&Card {
+                suit: SuitEnum::Spade,
+                number: NumberEnum::Seven
+            }));
+            assert!(output.contains(&Card {
+                suit: SuitEnum::Heart,
+                number: NumberEnum::Seven
+            }));
+            assert!(output.contains(&Card {
+                suit: SuitEnum::Diamond,
+                number: NumberEnum::Seven
+            }));
+        }
+
+        #[test]
+        fn can_play_card() {
+            let mut game_board = GameBoard::new(&RuleSet::default()).unwrap();
+
+            match game_board.play_card(Card {
+                suit: SuitEnum::Diamond,
+                number: NumberEnum::Seven,
+            }) {
+                Ok(_) => {}
+                Err(e) => panic!("Error playing diamond 7 on empty gameboard: {e}"),
+            };
+        }
+
+        fn opening_rules(opening_card: Card) -> RuleSet {
+            // built by hand so the board's own validation can be tested
+            RuleSet {
+                opening: OpeningRule::new(
+                    OpeningCard::Card(opening_card),
+                    FirstPlayer::LeftOfDealer,
+                ),
+                ..RuleSet::default()
+            }
+        }
+
+        #[test]
+        fn opening_card_is_only_playable_card_on_empty_board() {
+            let seven_of_diamonds = Card {
+                suit: SuitEnum::Diamond,
+                number: NumberEnum::Seven,
+            };
+            let game_board = GameBoard::new(&opening_rules(seven_of_diamonds.clone())).unwrap();
+
+            let output = game_board.get_playable_cards().unwrap().unwrap();
+
+            assert_eq!(output, vec![seven_of_diamonds]);
+        }
+
+        #[test]
+        fn opening_card_must_be_played_first() {
+            let seven_of_diamonds = Card {
+                suit: SuitEnum::Diamond,
+                number: NumberEnum::Seven,
+            };
+            let mut game_board = GameBoard::new(&opening_rules(seven_of_diamonds.clone())).unwrap();
+
+            let output = game_board.play_card(Card {
+                suit: SuitEnum::Club,
+                number: NumberEnum::Seven,
+            });
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                GameBoardError::OpeningCardRequired(seven_of_diamonds.clone()).to_string()
+            );
+
+            game_board.play_card(seven_of_diamonds).unwrap();
+            // once opened, the other sevens become playable as normal
+            let output = game_board.get_playable_cards().unwrap().unwrap();
+            assert_eq!(output.len(), 5);
+        }
+
+        #[test]
+        fn opening_card_must_open_a_stack() {
+            let six_of_diamonds = Card {
+                suit: SuitEnum::Diamond,
+                number: NumberEnum::Six,
+            };
+
+            let output = GameBoard::new(&opening_rules(six_of_diamonds.clone()));
+
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                GameBoardError::InvalidOpeningCard(six_of_diamonds).to_string()
+            );
+        }
+
+        #[test]
+        fn separate_rows_are_opened_by_each_copy() {
+            let rules = RuleSet::builder()
+                .decks(2, DuplicateRows::Separate)
+                .build()
+                .unwrap();
+            let mut game_board = GameBoard::new(&rules).unwrap();
+            let seven_of_hearts = Card {
+                suit: SuitEnum::Heart,
+                number: NumberEnum::Seven,
+            };
+            let eight_of_hearts = Card {
+                suit: SuitEnum::Heart,
+                number: NumberEnum::Eight,
+            };
+
+            // identical cards are only listed once
+            assert_eq!(game_board.get_playable_cards().unwrap().unwrap().len(), 4);
+
+            game_board.play_card(seven_of_hearts.clone()).unwrap();
+            game_board.play_card(eight_of_hearts.clone()).unwrap();
+            let output = game_board.get_playable_cards().unwrap().unwrap();
+            // the second seven opens a row of its own, where the second eight will go
+            assert!(output.contains(&seven_of_hearts));
+            assert!(!output.contains(&eight_of_hearts));
+
+            game_board.play_card(seven_of_hearts.clone()).unwrap();
+            let output = game_board.get_playable_cards().unwrap().unwrap();
+            assert!(output.contains(&eight_of_hearts));
+            game_board.play_card(eight_of_hearts.clone()).unwrap();
+            let output = game_board.get_playable_cards().unwrap().unwrap();
+            assert!(!output.contains(&seven_of_hearts));
+            assert!(!output.contains(&eight_of_hearts));
+
+            let output = game_board.play_card(seven_of_hearts);
+            assert!(output.is_err());
+        }
+
+        #[test]
+        fn cannot_play_unplayable_card() {
+            let mut game_board = GameBoard::new(&RuleSet::default()).unwrap();
+
+            let output = game_board.play_card(Card {
+                suit: SuitEnum::Diamond,
+                number: NumberEnum::Six,
+            });
+
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                GameBoardError::StackError(
+                    StackError::UnplayableCardNumber,
+                    "Diamonds".to_string()
+                )
+                .to_string()
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Player {
+    hand: Vec<Card>,
+    /// Chips won or lost so far this deal.
+    chips: i32,
+    /// Players on the same team share their result. Without partnerships every player is a
+    /// team of their own.
+    team: u8,
+}
+
+impl Player {
+    fn new() -> Player {
+        Player {
+            hand: Vec::new(),
+            chips: 0,
+            team: 0,
+        }
+    }
+}
+
+mod game_state {
+
+    use super::{distribute_cards, generate_new_shuffle, Player};
+    use crate::card_and_enums::Card;
+    use crate::game_board::{GameBoard, GameBoardError};
+    use crate::rule_set::{
+        BonusTurn, DealingScheme, EndCondition, FirstPlayer, KnockPenalty, OpeningCard,
+        PartnershipWin, RuleSet, WildReplacement,
+    };
+    use crate::stack::Played;
+    use std::sync::Arc;
+    use thiserror::Error;
+
+    #[derive(Debug, Clone)]
+    pub struct GameState {
+        game_board: GameBoard,
+        pub players: Vec<Player>,
+        pub player_turn: u8,
+        pub dealer: u8,
+        pub rules: Arc<RuleSet>,
+        /// Number of knocks since a card was last played.
+        pub consecutive_knocks: u8,
+        /// Chips paid in by antes and knocks, taken by the winner.
+        pub pot: u32,
+        /// Number of turns taken so far, counting knocks.
+        pub turns: u32,
+        /// Face-up cards left undealt, laid on the table as soon as they can be played.
+        pub widow: Vec<Card>,
+        /// Seats in the order they went out.
+        pub finishing_order: Vec<u8>,
+        /// The seat that played the last card, if any has been played.
+        pub last_player: Option<u8>,
+        /// Face-down cards drawn by a player who cannot play, in no known order.
+        pub stock: Vec<Card>,
+    }
+
+    #[derive(Debug, Error)]
+    pub enum GameStateError {
+        #[error("Players exceeded {0} player limit")]
+        TooManyPlayers(usize),
+
+        #[error("u8 overflow error")]
+        OverflowError,
+
+        #[error("GameBoard Error: {0}")]
+        GameBoardError(#[from] GameBoardError),
+
+        #[error("Called {0} on a state with more than one playable card")]
+        MoreThanOnePlayableCard(String),
+
+        #[error("Called {0} on a state with no playable card")]
+        NoPlayableCard(String),
+
+        #[error("Called play_card_and_return on a state with only one playable card, consider using play_only_playable_card")]
+        OnlyOnePlayableCard,
+
+        #[error("Attempted to play an unplayable card in play_card_and_return")]
+        UnplayableCard,
+
+        #[error("Player {0} attempted to play a card they do not hold")]
+        CardNotInHand(u8),
+
+        #[error("Dealer {0} is not seated at the table")]
+        DealerOutOfRange(u8),
+
+        #[error("No player holds the opening card")]
+        OpeningCardNotHeld,
+
+        #[error("{0:?} is not a wild card")]
+        NotWildCard(Card),
+
+        #[error("Partnerships need an even table of at least four, not {0}")]
+        UnevenPartnerships(usize),
+
+        #[error("No card can be passed on this knock")]
+        NoKnockTransfer,
+
+        #[error("There is no card {0} in the stock")]
+        NotInStock(usize),
+
+        #[error("Runs of more than one card are not allowed")]
+        RunsNotAllowed,
+    }
+
+    impl GameState {
+        /// Deals a new game played by `rules`, with `dealer` dealing the cards.
+        #[allow(clippy::needless_return, clippy::redundant_field_names)]
+        pub fn new(
+            number_of_players: usize,
+            rules: impl Into<Arc<RuleSet>>,
+            dealer: u8,
+        ) -> Result<GameState, GameStateError> {
+            let rules: Arc<RuleSet> = rules.into();
+            if number_of_players > rules.max_players() {
+                return Err(GameStateError::TooManyPlayers(rules.max_players()));
+            }
+            if dealer as usize >= number_of_players {
+                return Err(GameStateError::DealerOutOfRange(dealer));
+            }
+            if rules.partnership.is_some()
+                && (number_of_players < 4 || !number_of_players.is_multiple_of(2))
+            {
+                return Err(GameStateError::UnevenPartnerships(number_of_players));
+            }
+            let deck = generate_new_shuffle(&rules);
+            let (mut players, mut undealt, stock) = distribute_cards(
+                number_of_players,
+                deck,
+                &rules.dealing,
+                dealer,
+                rules.hand_size.map(|size| size as usize),
+            );
+            for (seat, player) in players.iter_mut().enumerate() {
+                // partners sit opposite each other
+                player.team = match rules.partnership {
+                    Some(_) => (seat % (number_of_players / 2)) as u8,
+                    None => seat as u8,
+                };
+            }
+            let opening_rule = &rules.opening;
+            let left_of_dealer = ((dealer as usize + 1) % number_of_players) as u8;
+            if rules.dealing == DealingScheme::ExtrasToLeader {
+                let leader = match (opening_rule.first_player, &opening_rule.opening_card) {
+                    (FirstPlayer::HolderOfOpeningCard, OpeningCard::Card(card)) => players
+                        .iter()
+                        .position(|player| player.hand.contains(card))
+                        .unwrap_or(left_of_dealer as usize),
+                    _ => left_of_dealer as usize,
+                };
+                players[leader].hand.append(&mut undealt);
+            }
+            let mut pot: u32 = 0;
+            if let Some(chips) = rules.chips {
+                for player in players.iter_mut() {
+                    player.chips -= chips.ante as i32;
+                    pot += chips.ante;
+                }
+            }
+            let mut game_state = GameState {
+                game_board: GameBoard::new(&rules)?,
+                players: players,
+                player_turn: left_of_dealer,
+                rules: rules,
+                consecutive_knocks: 0,
+                turns: 0,
+                widow: undealt,
+                finishing_order: Vec::new(),
+                last_player: None,
+                stock: stock,
+                pot: pot,
+                dealer: dealer,
+            };
+            game_state.open()?;
+            return Ok(game_state);
+        }
+
+        /// Lays the dealt widow and seats the player who leads.
+        fn open(&mut self) -> Result<(), GameStateError> {
+            let left_of_dealer = ((self.dealer as usize + 1) % self.players.len()) as u8;
+            // the widow is face up from the start, so it can open the game itself
+            self.play_widow_cards()?;
+            let opening_rule = &self.rules.opening;
+            self.player_turn = match (opening_rule.first_player, &opening_rule.opening_card) {
+                (FirstPlayer::LeftOfDealer, _) => left_of_dealer,
+                (FirstPlayer::HolderOfOpeningCard, OpeningCard::Card(card)) => {
+                    match self
+                        .players
+                        .iter()
+                        .position(|player| player.hand.contains(card))
+                    {
+                        Some(seat) => seat as u8,
+                        // the opening card was in the widow and is already down
+                        None if !self.game_board.is_empty() => left_of_dealer,
+                        None => return Err(GameStateError::OpeningCardNotHeld),
+                    }
+                }
+                (FirstPlayer::HolderOfOpeningCard, OpeningCard::Any) => {
+                    return Err(GameStateError::OpeningCardNotHeld)
+                }
+            };
+            Ok(())
+        }
+
+        #[allow(clippy::needless_return)]
+        pub fn pass_turn(&mut self) -> Result<(), GameStateError> {
+            if self.player_turn == u8::MAX {
+                return Err(GameStateError::OverflowError);
+            }
+            self.turns += 1;
+            // players who have gone out are skipped, unless no one has cards left
+            for _seat in 0..self.players.len() {
+                if self.player_turn < self.players.len() as u8 - 1 {
+                    self.player_turn += 1;
+                } else {
+                    self.player_turn = 0;
+                }
+                if !self.players[self.player_turn as usize].hand.is_empty() {
+                    break;
+                }
+            }
+            return Ok(());
+        }
+
+        /// Passes the turn without playing a card.
+        pub fn knock(&mut self) -> Result<(), GameStateError> {
+            if let Some(chips) = self.rules.chips {
+                self.players[self.player_turn as usize].chips -= chips.knock_fee as i32;
+                self.pot += chips.knock_fee;
+            }
+            self.consecutive_knocks = self.consecutive_knocks.saturating_add(1);
+            self.pass_turn()
+        }
+
+        /// Returns each seat's net chips if the game ends with `winner` going out: the winner
+        /// takes the pot, and is paid for every card left in the hands of the other teams.
+        pub fn settle(&self, winner: u8) -> Vec<i32> {
+            let mut output: Vec<i32> = self.players.iter().map(|player| player.chips).collect();
+            if let Some(chips) = self.rules.chips {
+                for (seat, player) in self.players.iter().enumerate() {
+                    // partners do not pay each other
+                    if player.team != self.team(winner) {
+                        let owed = (chips.per_card as usize * player.hand.len()) as i32;
+                        output[seat] -= owed;
+                        output[winner as usize] += owed;
+                    }
+                }
+                output[winner as usize] += self.pot as i32;
+            }
+            output
+        }
+
+        pub fn knock_and_return_new(&self) -> Result<GameState, GameStateError> {
+            let mut output = self.clone();
+            output.knock()?;
+            Ok(output)
+        }
+
+        /// Returns the seats a card passes from and to when the current player knocks, if the
+        /// knock penalty applies.
+        fn knock_transfer_seats(&self) -> Option<(u8, u8)> {
+            let (from, to) = match self.rules.knock_penalty? {
+                KnockPenalty::TakeFromLast => (self.last_player?, self.player_turn),
+                KnockPenalty::GiveToNext => {
+                    let next = (1..self.players.len())
+                        .map(|offset| {
+                            ((self.player_turn as usize + offset) % self.players.len()) as u8
+                        })
+                        .find(|seat| !self.players[*seat as usize].hand.is_empty())?;
+                    (self.player_turn, next)
+                }
+            };
+            // a player's last card is never passed, so no one goes out by knocking
+            if from == to || self.players[from as usize].hand.len() < 2 {
+                return None;
+            }
+            Some((from, to))
+        }
+
+        /// Returns the cards the current player can choose between passing if they knock.
+        /// Empty if no card changes hands.
+        pub fn get_knock_transfers(&self) -> Vec<Card> {
+            match self.knock_transfer_seats() {
+                Some((from, _)) => self.players[from as usize].hand.clone(),
+                None => Vec::new(),
+            }
+        }
+
+        /// Knocks, passing `card` as the knock penalty requires.
+        pub fn knock_with_transfer(&mut self, card: Card) -> Result<(), GameStateError> {
+            let (from, to) = match self.knock_transfer_seats() {
+                Some(seats) => seats,
+                None => return Err(GameStateError::NoKnockTransfer),
+            };
+            let position = match self.players[from as usize]
+                .hand
+                .iter()
+                .position(|held| *held == card)
+            {
+                Some(position) => position,
+                None => return Err(GameStateError::CardNotInHand(from)),
+            };
+            let card = self.players[from as usize].hand.remove(position);
+            self.players[to as usize].hand.push(card);
+            self.knock()
+        }
+
+        /// Moves the stock card at `index` into the current player's hand and passes the turn.
+        /// While the stock lasts, drawing takes the place of any knock penalty.
+        pub fn draw_and_pass(&mut self, index: usize) -> Result<(), GameStateError> {
+            if index >= self.stock.len() {
+                return Err(GameStateError::NotInStock(index));
+            }
+            let card = self.stock.remove(index);
+            self.players[self.player_turn as usize].hand.push(card);
+            // a drawn card can change what can be played, so the round of knocks starts again
+            self.consecutive_knocks = 0;
+            self.knock()
+        }
+
+        /// Returns every state the current player can reach by drawing from the stock, one for
+        /// each card they may draw. Each is equally likely.
+        pub fn draw_options(&self) -> Result<Vec<GameState>, GameStateError> {
+            let mut output = Vec::new();
+            for index in 0..self.stock.len() {
+                let mut next_state = self.clone();
+                next_state.draw_and_pass(index)?;
+                output.push(next_state);
+            }
+            Ok(output)
+        }
+
+        /// Returns every state the current player can reach by knocking, one for each card
+        /// they could pass under the knock penalty.
+        pub fn knock_options(&self) -> Result<Vec<GameState>, GameStateError> {
+            let transfers = self.get_knock_transfers();
+            if transfers.is_empty() {
+                return Ok(vec![self.knock_and_return_new()?]);
+            }
+            let mut output = Vec::new();
+            for card in transfers {
+                let mut next_state = self.clone();
+                next_state.knock_with_transfer(card)?;
+                output.push(next_state);
+            }
+            Ok(output)
+        }
+
+        /// Returns true once every player still holding cards has knocked in a row, so the
+        /// position would repeat.
+        pub fn is_stalemate(&self) -> bool {
+            self.knock_rounds() >= 1
+        }
+
+        /// Returns true if any player still holding cards could play, whoever's turn it is.
+        pub fn anyone_can_play(&self) -> Result<bool, GameStateError> {
+            let mut view = self.clone();
+            for seat in 0..self.players.len() {
+                if self.players[seat].hand.is_empty() {
+                    continue;
+                }
+                view.player_turn = seat as u8;
+                if view.get_playable_cards()?.is_some() || !view.get_wild_plays()?.is_empty() {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+
+        /// Returns how many times every player still holding cards has knocked in a row.
+        pub fn knock_rounds(&self) -> usize {
+            let in_play = self
+                .players
+                .iter()
+                .filter(|player| !player.hand.is_empty())
+                .count();
+            self.consecutive_knocks as usize / in_play.max(1)
+        }
+
+        /// Returns a seat on the team that has won, if the game is over.
+        pub fn winning_seat(&self) -> Option<u8> {
+            let out = |seat: usize| self.players[seat].hand.is_empty();
+            let seat = match self.rules.partnership {
+                None | Some(PartnershipWin::FirstPartnerOut) => {
+                    (0..self.players.len()).find(|seat| out(*seat))
+                }
+                Some(PartnershipWin::BothPartnersOut) => (0..self.players.len()).find(|seat| {
+                    self.players
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, player)| player.team == self.players[*seat].team)
+                        .all(|(partner, _)| out(partner))
+                }),
+            };
+            seat.map(|seat| seat as u8)
+        }
+
+        /// Returns the team `seat` plays for.
+        pub fn team(&self, seat: u8) -> u8 {
+            self.players[seat as usize].team
+        }
+
+        #[allow(clippy::needless_return)]
+        pub fn play_only_playable_card(&mut self) -> Result<(), GameStateError> {
+            let playable = self.get_playable_cards()?;
+            let card = match playable {
+                Some(card) => {
+                    if card.len() > 1 {
+                        return Err(GameStateError::MoreThanOnePlayableCard(
+                            "play_only_playable_card".to_string(),
+                        ));
+                    } else {
+                        card[0].to_owned()
+                    }
+                }
+                None => {
+                    return Err(GameStateError::NoPlayableCard(
+                        "play_only_playable_card".to_string(),
+                    ))
+                }
+            };
+            let played = self.play_card_from_hand(card)?;
+            self.end_turn(&played)?;
+            return Ok(());
+        }
+
+        #[allow(clippy::needless_return)]
+        pub fn play_card_and_return_new(&self, card: Card) -> Result<GameState, GameStateError> {
+            if !self.players[self.player_turn as usize].hand.contains(&card) {
+                return Err(GameStateError::CardNotInHand(self.player_turn));
+            }
+            let get_playable = self.get_playable_cards()?;
+            let playable_cards = match get_playable {
+                Some(result) => match result.len() {
+                    0 => {
+                        return Err(GameStateError::NoPlayableCard(
+                            "play_card_and_return".to_string(),
+                        ))
+                    }
+                    1 => return Err(GameStateError::OnlyOnePlayableCard),
+                    _ => result,
+                },
+                None => {
+                    return Err(GameStateError::NoPlayableCard(
+                        "play_card_and_return".to_string(),
+                    ))
+                }
+            };
+            if !playable_cards.contains(&card) {
+                return Err(GameStateError::UnplayableCard);
+            } else {
+                let mut output = self.clone();
+                let played = output.play_card_from_hand(card)?;
+                output.end_turn(&played)?;
+                return Ok(output);
+            }
+        }
+
+        /// Plays any legal `card` for the current player and passes the turn, regardless of
+        /// how many other cards they could have played.
+        pub fn play_card(&mut self, card: Card) -> Result<(), GameStateError> {
+            if !self.players[self.player_turn as usize].hand.contains(&card) {
+                return Err(GameStateError::CardNotInHand(self.player_turn));
+            }
+            let playable_cards = self.get_playable_cards()?.unwrap_or_default();
+            if !playable_cards.contains(&card) {
+                return Err(GameStateError::UnplayableCard);
+            }
+            let played = self.play_card_from_hand(card)?;
+            self.end_turn(&played)?;
+            Ok(())
+        }
+
+        /// Lays `cards` as a run for the current player and passes the turn.
+        pub fn play_run(&mut self, cards: Vec<Card>) -> Result<(), GameStateError> {
+            if cards.len() > 1 && !self.rules.runs {
+                return Err(GameStateError::RunsNotAllowed);
+            }
+            let played = self.play_cards_from_hand(&cards)?;
+            self.end_turn(&played)?;
+            Ok(())
+        }
+
+        /// Returns every run of two or more cards the current player can lay in one turn, each
+        /// in the order its cards are played. Empty unless runs are allowed.
+        pub fn get_runs(&self) -> Result<Vec<Vec<Card>>, GameStateError> {
+            if !self.rules.runs {
+                return Ok(vec![]);
+            }
+            let hand = &self.players[self.player_turn as usize].hand;
+            let mut output: Vec<Vec<Card>> = Vec::new();
+            let mut partial: Vec<(GameBoard, Vec<Card>)> = Vec::new();
+            for card in self.get_playable_cards()?.unwrap_or_default() {
+                let mut board = self.game_board.clone();
+                board.play_card(card.clone())?;
+                partial.push((board, vec![card]));
+            }
+            // extend each run by every held card that plays straight on from its last card
+            while let Some((board, run)) = partial.pop() {
+                let last = &run[run.len() - 1];
+                for card in board.get_playable_cards()?.unwrap_or_default() {
+                    if !hand.contains(&card) || run.contains(&card) {
+                        continue;
+                    }
+                    if !board.continues_run(last, &card) {
+                        continue;
+                    }
+                    let mut next_board = board.clone();
+                    next_board.play_card(card.clone())?;
+                    let mut next_run = run.clone();
+                    next_run.push(card);
+                    output.push(next_run.clone());
+                    partial.push((next_board, next_run));
+                }
+            }
+            Ok(output)
+        }
+
+        /// Plays the wild card `wild` from the current player's hand in place of `card`, and
+        /// passes the turn.
+        pub fn play_wild(&mut self, wild: Card, card: Card) -> Result<(), GameStateError> {
+            let position = self.position_in_hand(&wild)?;
+            match self.rules.wilds {
+                Some(wilds) if wilds.is_wild(&wild) => (),
+                _ => return Err(GameStateError::NotWildCard(wild)),
+            }
+            if !self.game_board.get_wild_placements()?.contains(&card) {
+                return Err(GameStateError::UnplayableCard);
+            }
+            let played = self.game_board.play_wild(wild, card)?;
+            self.players[self.player_turn as usize]
+                .hand
+                .remove(position);
+            self.record_if_out();
+            self.play_widow_cards()?;
+            self.consecutive_knocks = 0;
+            self.last_player = Some(self.player_turn);
+            self.end_turn(&[played])?;
+            Ok(())
+        }
+
+        /// Returns every way the current player can play a wild card, as pairs of the wild
+        /// card and the card it would stand in for.
+        pub fn get_wild_plays(&self) -> Result<Vec<(Card, Card)>, GameStateError> {
+            let wild_rule = match self.rules.wilds {
+                Some(wild_rule) => wild_rule,
+                None => return Ok(vec![]),
+            };
+            let mut wilds: Vec<Card> = Vec::new();
+            for card in self.players[self.player_turn as usize].hand.iter() {
+                if wild_rule.is_wild(card) && !wilds.contains(card) {
+                    wilds.push(card.clone());
+                }
+            }
+            if wilds.is_empty() {
+                return Ok(vec![]);
+            }
+            let placements = self.game_board.get_wild_placements()?;
+            let mut output: Vec<(Card, Card)> = Vec::new();
+            for wild in wilds {
+                // a wild number standing in for itself is just an ordinary play
+                for card in placements.iter().filter(|card| **card != wild) {
+                    output.push((wild.clone(), card.clone()));
+                }
+            }
+            Ok(output)
+        }
+
+        /// Moves `card` from the current player's hand onto the board. If it replaces a wild
+        /// card that is reclaimed, the wild card goes into their hand.
+        fn play_card_from_hand(&mut self, card: Card) -> Result<Vec<Played>, GameStateError> {
+            self.play_cards_from_hand(&[card])
+        }
+
+        /// Moves `cards` from the current player's hand to the board as a single play,
+        /// returning what each card did. The turn is left with the player.
+        fn play_cards_from_hand(&mut self, cards: &[Card]) -> Result<Vec<Played>, GameStateError> {
+            for card in cards {
+                self.position_in_hand(card)?;
+            }
+            let played = self
+                .game_board
+                .play_run(cards)
+                .map_err(GameStateError::GameBoardError)?;
+            let replaced = played.iter().filter_map(|outcome| outcome.replaced.clone());
+            let hand = &mut self.players[self.player_turn as usize].hand;
+            for card in cards {
+                if let Some(position) = hand.iter().position(|held| held == card) {
+                    hand.remove(position);
+                }
+            }
+            if let Some(wilds) = self.rules.wilds {
+                if wilds.replacement == WildReplacement::Reclaimed {
+                    hand.extend(replaced);
+                }
+            }
+            self.record_if_out();
+            self.play_widow_cards()?;
+            self.consecutive_knocks = 0;
+            self.last_player = Some(self.player_turn);
+            Ok(played)
+        }
+
+        /// Passes the turn after a play, unless the play earns the player a bonus turn.
+        fn end_turn(&mut self, played: &[Played]) -> Result<(), GameStateError> {
+            let bonus = match self.rules.bonus_turn {
+                None => false,
+                Some(BonusTurn::CompletedDirection) => {
+                    played.iter().any(|outcome| outcome.completed)
+                }
+                Some(BonusTurn::Centre) => played.iter().any(|outcome| outcome.opened),
+            };
+            // a player who has just gone out has nothing to take a bonus turn with
+            if bonus && !self.players[self.player_turn as usize].hand.is_empty() {
+                self.turns += 1;
+                return Ok(());
+            }
+            self.pass_turn()
+        }
+
+        /// Adds the current player to the finishing order if they have just gone out.
+        fn record_if_out(&mut self) {
+            if self.players[self.player_turn as usize].hand.is_empty()
+                && !self.finishing_order.contains(&self.player_turn)
+            {
+                self.finishing_order.push(self.player_turn);
+            }
+        }
+
+        /// Returns bytes identifying the position: the board, every hand, whose turn it is and
+        /// anything else the rest of the game depends on. Positions reached by different
+        /// orders of play share a key, since the order of cards in a hand or the stock does
+        /// not matter, and the number of turns taken is left out.
+        pub fn key(&self) -> Vec<u8> {
+            let sorted = |cards: &Vec<Card>| {
+                let mut indices: Vec<u8> = cards.iter().map(|card| card.index()).collect();
+                indices.sort();
+                indices
+            };
+            let mut output: Vec<u8> = Vec::new();
+            self.game_board.extend_key(&mut output);
+            for player in self.players.iter() {
+                let hand = sorted(&player.hand);
+                output.push(hand.len() as u8);
+                output.extend(hand);
+                if self.rules.chips.is_some() {
+                    output.extend_from_slice(&player.chips.to_le_bytes());
+                }
+            }
+            for cards in [&self.widow, &self.stock] {
+                let cards = sorted(cards);
+                output.push(cards.len() as u8);
+                output.extend(cards);
+            }
+            output.push(self.player_turn);
+            output.push(self.consecutive_knocks);
+            output.extend_from_slice(&self.pot.to_le_bytes());
+            if self.rules.end == EndCondition::FullOrder {
+                output.push(self.finishing_order.len() as u8);
+                output.extend_from_slice(&self.finishing_order);
+            }
+            if self.rules.knock_penalty == Some(KnockPenalty::TakeFromLast) {
+                output.push(self.last_player.unwrap_or(u8::MAX));
+            }
+            output
+        }
+
+        /// Returns every seat in finishing order once at most one player still holds cards,
+        /// with that player last.
+        pub fn placings(&self) -> Option<Vec<u8>> {
+            let in_play: Vec<u8> = (0..self.players.len() as u8)
+                .filter(|seat| !self.players[*seat as usize].hand.is_empty())
+                .collect();
+            if in_play.len() > 1 {
+                return None;
+            }
+            let mut output = self.finishing_order.clone();
+            output.extend(in_play);
+            Some(output)
+        }
+
+        /// Lays every widow card that has become playable on the table. In a Domino game the
+        /// widow waits until a player has chosen the centre.
+        fn play_widow_cards(&mut self) -> Result<(), GameStateError> {
+            if self.game_board.centre().is_none()
+                && self.rules.opening.opening_card == OpeningCard::Any
+            {
+                return Ok(());
+            }
+            loop {
+                let playable = self.game_board.get_playable_cards()?.unwrap_or_default();
+                match self.widow.iter().position(|card| playable.contains(card)) {
+                    Some(position) => {
+                        let card = self.widow.remove(position);
+                        self.game_board.play_card(card)?;
+                    }
+                    None => return Ok(()),
+                }
+            }
+        }
+
+        fn position_in_hand(&self, card: &Card) -> Result<usize, GameStateError> {
+            match self.players[self.player_turn as usize]
+                .hand
+                .iter()
+                .position(|held| held == card)
+            {
+                Some(position) => Ok(position),
+                None => Err(GameStateError::CardNotInHand(self.player_turn)),
+            }
+        }
+
+        /// Returns the cards the current player can legally play: those playable on the board
+        /// which are also in their hand. `None` means the player has to knock.
+        pub fn get_playable_cards(&self) -> Result<Option<Vec<Card>>, GameStateError> {
+            let board_playable = match self.game_board.get_playable_cards() {
+                Ok(cards_option) => cards_option,
+                Err(e) => return Err(GameStateError::GameBoardError(e)),
+            };
+            let hand = &self.players[self.player_turn as usize].hand;
+            match board_playable {
+                Some(cards) => {
+                    let useful_cards: Vec<Card> = cards
+                        .into_iter()
+                        .filter(|card| hand.contains(card))
+                        .collect();
+                    if useful_cards.is_empty() {
+                        return Ok(None);
+                    }
+                    Ok(Some(useful_cards))
+                }
+                None => Ok(None),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+
+        use crate::{
+            card_and_enums::{NumberEnum, SuitEnum},
+            rule_set::{CentreRule, ChipRule, DuplicateRows, OpeningRule, WildRule},
+            stack::Stack,
+        };
+
+        use super::*;
+        use crate::test_helpers::{card, cards, custom_deck};
+
+        #[test]
+        #[allow(clippy::unnecessary_cast)]
+        fn initialization_with_valid_player_count() {
+            let game_state = GameState::new(4, RuleSet::default(), 3);
+
+            assert!(game_state.is_ok());
+            let game_state = game_state.unwrap();
+
+            assert_eq!(game_state.players.len(), 4);
+            assert_eq!(game_state.player_turn, 0 as u8);
+        }
+
+        #[test]
+        fn initialization_with_invalid_player_count() {
+            let game_state = GameState::new(30, RuleSet::default(), 29);
+
+            assert!(game_state.is_err());
+            let game_state = game_state.unwrap_err();
+
+            assert_eq!(
+                game_state.to_string(),
+                GameStateError::TooManyPlayers(26).to_string()
+            );
+        }
+
+        fn opening_rules(opening: OpeningRule) -> RuleSet {
+            RuleSet::builder().opening(opening).build().unwrap()
+        }
+
+        fn seven_of_diamonds() -> OpeningCard {
+            OpeningCard::Card(Card {
+                suit: SuitEnum::Diamond,
+                number: NumberEnum::Seven,
+            })
+        }
+
+        #[test]
+        fn player_left_of_dealer_starts() {
+            let game_state = GameState::new(
+                4,
+                opening_rules(OpeningRule::new(
+                    OpeningCard::Any,
+                    FirstPlayer::LeftOfDealer,
+                )),
+                1,
+            )
+            .unwrap();
+
+            assert_eq!(game_state.dealer, 1);
+            assert_eq!(game_state.player_turn, 2);
+        }
+
+        #[test]
+        fn player_left_of_last_seat_dealer_is_first_seat() {
+            let game_state = GameState::new(
+                4,
+                opening_rules(OpeningRule::new(
+                    seven_of_diamonds(),
+                    FirstPlayer::LeftOfDealer,
+                )),
+                3,
+            )
+            .unwrap();
+
+            assert_eq!(game_state.player_turn, 0);
+        }
+
+        #[test]
+        fn holder_of_opening_card_starts() {
+            let game_state = GameState::new(
+                4,
+                opening_rules(OpeningRule::new(
+                    seven_of_diamonds(),
+                    FirstPlayer::HolderOfOpeningCard,
+                )),
+                0,
+            )
+            .unwrap();
+
+            let playables = game_state.get_playable_cards().unwrap().unwrap();
+            assert_eq!(
+                playables,
+                vec![Card {
+                    suit: SuitEnum::Diamond,
+                    number: NumberEnum::Seven,
+                }]
+            );
+        }
+
+        #[test]
+        fn holder_of_opening_card_requires_an_opening_card() {
+            let output = GameState::new(
+                4,
+                opening_rules(OpeningRule::new(
+                    OpeningCard::Any,
+                    FirstPlayer::HolderOfOpeningCard,
+                )),
+                0,
+            );
+
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                GameStateError::OpeningCardNotHeld.to_string()
+            );
+        }
+
+        #[test]
+        fn game_is_played_by_its_rules() {
+            let rules = RuleSet::builder()
+                .centre(CentreRule::Fixed(NumberEnum::Eight))
+                .build()
+                .unwrap();
+            let mut game_state = GameState::new(4, rules, 3).unwrap();
+            game_state.players[0].hand = vec![
+                Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Seven,
+                },
+                Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Eight,
+                },
+            ];
+
+            let playables = game_state.get_playable_cards().unwrap().unwrap();
+
+            assert_eq!(
+                game_state.rules.centre,
+                CentreRule::Fixed(NumberEnum::Eight)
+            );
+            assert_eq!(
+                playables,
+                vec![Card {
+                    suit: SuitEnum::Club,
+                    number: NumberEnum::Eight,
+                }]
+            );
+        }
+
+        fn fan_tan_rules() -> RuleSet {
+            RuleSet::builder()
+                .chips(ChipRule {
+                    ante: 2,
+                    knock_fee: 1,
+                    per_card: 3,
+                })
+                .build()
+                .unwrap()
+        }
+
+        #[test]
+        fn every_player_antes_into_the_pot() {
+            let game_state = GameState::new(4, fan_tan_rules(), 3).unwrap();
+
+            assert_eq!(game_state.pot, 8);
+            for player in game_state.players.iter() {
+                assert_eq!(player.chips, -2);
+            }
+        }
+
+        #[test]
+        fn knocking_pays_into_the_pot() {
+            let mut game_state = GameState::new(4, fan_tan_rules(), 3).unwrap();
+
+            game_state.knock().unwrap();
+
+            assert_eq!(game_state.pot, 9);
+            assert_eq!(game_state.players[0].chips, -3);
+            assert_eq!(game_state.players[1].chips, -2);
+        }
+
+        #[test]
+        fn winner_takes_pot_and_card_payments() {
+            let mut game_state = GameState::new(3, fan_tan_rules(), 2).unwrap();
+            game_state.knock().unwrap();
+            game_state.players[0].hand = vec![];
+            game_state.players[1].hand = vec![Card {
+                suit: SuitEnum::Club,
+                number: NumberEnum::Two,
+            }];
+            game_state.players[2].hand = vec![];
+
+            let settlement = game_state.settle(2);
+
+            // pot of 7 plus 3 from player 2's card, less the ante
+            assert_eq!(settlement, vec![-3, -5, 8]);
+            assert_eq!(settlement.iter().sum::<i32>(), 0);
+        }
+
+        #[test]
+        fn settlement_without_chips_is_zero() {
+            let game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+
+            assert_eq!(game_state.settle(0), vec![0, 0, 0]);
+        }
+
+        #[test]
+        fn dealer_must_be_seated() {
+            let output = GameState::new(4, RuleSet::default(), 4);
+
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                GameStateError::DealerOutOfRange(4).to_string()
+            );
+        }
+
+        #[test]
+        #[allow(clippy::unnecessary_cast)]
+        fn pass_turn_advances_player_turn() {
+            let game_state = GameState::new(4, RuleSet::default(), 3);
+            assert!(game_state.is_ok());
+            let mut game_state = game_state.unwrap();
+
+            assert_eq!(game_state.player_turn, 0 as u8);
+            let output = game_state.pass_turn();
+            assert!(output.is_ok());
+            assert_eq!(game_state.player_turn, 1 as u8);
+            assert_eq!(game_state.turns, 1);
+        }
+
+        #[test]
+        #[allow(clippy::unnecessary_cast)]
+        fn pass_turn_catches_overflow_err() {
+            let game_state = GameState::new(4, RuleSet::default(), 3);
+            assert!(game_state.is_ok());
+            let mut game_state = game_state.unwrap();
+
+            game_state.player_turn = 255 as u8;
+            let output = game_state.pass_turn();
+            assert!(output.is_err());
+            let output = output.unwrap_err();
+            assert_eq!(
+                output.to_string(),
+                GameStateError::OverflowError.to_string()
+            );
+        }
+
+        #[test]
+        #[allow(clippy::unnecessary_cast)]
+        fn pass_turn_resets_to_0_after_last_player_turn() {
+            let game_state = GameState::new(3, RuleSet::default(), 2);
+            assert!(game_state.is_ok());
+            let mut game_state = game_state.unwrap();
+            game_state.player_turn = 2;
+
+            let output = game_state.pass_turn();
+            assert!(output.is_ok());
+            assert_eq!(game_state.player_turn, 0 as u8);
+        }
+
+        #[test]
+        fn get_playable_cards_only_returns_cards_in_hand() {
+            let mut game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+            game_state.players[0].hand = vec![
+                card(SuitEnum::Club, NumberEnum::Seven),
+                card(SuitEnum::Heart, NumberEnum::Seven),
+                card(SuitEnum::Heart, NumberEnum::Eight),
+            ];
+
+            let playables = game_state.get_playable_cards().unwrap().unwrap();
+
+            assert_eq!(playables.len(), 2);
+            assert!(playables.contains(&card(SuitEnum::Club, NumberEnum::Seven)));
+            assert!(playables.contains(&card(SuitEnum::Heart, NumberEnum::Seven)));
+        }
+
+        #[test]
+        fn get_playable_cards_returns_none_when_hand_has_no_playable_card() {
+            let mut game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+            game_state.players[0].hand = vec![
+                card(SuitEnum::Club, NumberEnum::Six),
+                card(SuitEnum::Heart, NumberEnum::King),
+            ];
+
+            let playables = game_state.get_playable_cards().unwrap();
+
+            assert!(playables.is_none());
+        }
+
+        #[test]
+        fn play_only_playable_card_errors_with_multiple_playable_cards() {
+            let mut game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+            game_state.players[0].hand = vec![
+                card(SuitEnum::Club, NumberEnum::Seven),
+                card(SuitEnum::Spade, NumberEnum::Seven),
+            ];
+            let output = game_state.play_only_playable_card();
+            assert!(output.is_err());
+            let output = output.unwrap_err();
+            assert_eq!(
+                output.to_string(),
+                GameStateError::MoreThanOnePlayableCard("play_only_playable_card".to_string())
+                    .to_string()
+            );
+        }
+
+        #[test]
+        fn play_only_playable_card_plays_with_one_playable_card() {
+            let mut game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+            let game_board = GameBoard::from(vec![
+                Stack::get_completed_stack(SuitEnum::Club),
+                Stack::get_completed_stack(SuitEnum::Spade),
+                Stack::get_completed_stack(SuitEnum::Heart),
+            ])
+            .unwrap();
+            game_state.game_board = game_board;
+            game_state.players[0].hand = vec![
+                card(SuitEnum::Diamond, NumberEnum::Seven),
+                card(SuitEnum::Diamond, NumberEnum::Two),
+            ];
+            let output = game_state.play_only_playable_card();
+            assert!(output.is_ok());
+
+            assert_eq!(game_state.player_turn, 1); // turn was passed
+
+            // the next player's hand is not known, so check the board directly
+            let playables = match game_state.game_board.get_playable_cards() {
+                Ok(cards) => cards.unwrap(),
+                Err(e) => panic!("{e}"),
+            };
+
+            // since we have played the seven of diamonds
+            // the next playable cards will be the six and the eight of diamonds
+            assert_eq!(playables.len(), 2);
+            assert!(playables.contains(&Card {
+                suit: SuitEnum::Diamond,
+                number: NumberEnum::Eight,
+            }));
+            assert!(playables.contains(&Card {
+                suit: SuitEnum::Diamond,
+                number: NumberEnum::Six,
+            }));
+        }
+
+        #[test]
+        fn play_only_playable_card_errors_with_no_playable_card() {
+            let mut game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+            let game_board = GameBoard::from(vec![
+                Stack::get_completed_stack(SuitEnum::Club),
+                Stack::get_completed_stack(SuitEnum::Spade),
+                Stack::get_completed_stack(SuitEnum::Heart),
+                Stack::get_completed_stack(SuitEnum::Diamond),
+            ])
+            .unwrap(); // get a completed board
+            game_state.game_board = game_board; // use it
+            let output = game_state.play_only_playable_card();
+            assert!(output.is_err());
+
+            let output = output.unwrap_err();
+            assert_eq!(
+                output.to_string(),
+                GameStateError::NoPlayableCard("play_only_playable_card".to_string()).to_string()
+            )
+        }
+
+        #[test]
+        fn play_card_and_return_new_succeeds() {
+            let mut game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+            game_state.players[0].hand = vec![
+                card(SuitEnum::Club, NumberEnum::Seven),
+                card(SuitEnum::Heart, NumberEnum::Seven),
+            ];
+            let output = game_state.play_card_and_return_new(Card {
+                suit: SuitEnum::Club,
+                number: NumberEnum::Seven,
+            });
+            assert!(output.is_ok());
+            let output = output.unwrap();
+            assert_eq!(output.player_turn, 1);
+            assert_eq!(output.players[0].hand.len(), 1);
+            assert!(!output.players[0]
+                .hand
+                .contains(&card(SuitEnum::Club, NumberEnum::Seven)));
+            // the original state is untouched
+            assert_eq!(game_state.players[0].hand.len(), 2);
+        }
+
+        #[test]
+        fn play_only_playable_card_removes_card_from_hand() {
+            let mut game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+            game_state.players[0].hand = vec![
+                card(SuitEnum::Diamond, NumberEnum::Seven),
+                card(SuitEnum::Diamond, NumberEnum::Two),
+            ];
+            game_state.play_only_playable_card().unwrap();
+
+            assert_eq!(
+                game_state.players[0].hand,
+                vec![card(SuitEnum::Diamond, NumberEnum::Two)]
+            );
+        }
+
+        #[test]
+        fn playing_one_of_two_identical_cards_keeps_the_other() {
+            let rules = RuleSet::builder()
+                .decks(2, DuplicateRows::Separate)
+                .build()
+                .unwrap();
+            let mut game_state = GameState::new(6, rules, 5).unwrap();
+            assert_eq!(
+                game_state
+                    .players
+                    .iter()
+                    .map(|player| player.hand.len())
+                    .sum::<usize>(),
+                104
+            );
+            game_state.players[0].hand = vec![
+                card(SuitEnum::Club, NumberEnum::Seven),
+                card(SuitEnum::Club, NumberEnum::Seven),
+                card(SuitEnum::Club, NumberEnum::Eight),
+            ];
+
+            game_state.play_only_playable_card().unwrap();
+
+            assert_eq!(
+                game_state.players[0].hand,
+                vec![
+                    card(SuitEnum::Club, NumberEnum::Seven),
+                    card(SuitEnum::Club, NumberEnum::Eight)
+                ]
+            );
+        }
+
+        #[test]
+        fn play_card_and_return_new_rejects_card_playable_on_board_but_not_in_hand() {
+            let mut game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+            game_state.players[0].hand = vec![
+                card(SuitEnum::Club, NumberEnum::Seven),
+                card(SuitEnum::Heart, NumberEnum::Seven),
+            ];
+            let output = game_state.play_card_and_return_new(Card {
+                suit: SuitEnum::Spade,
+                number: NumberEnum::Seven,
+            });
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                GameStateError::CardNotInHand(0).to_string()
+            );
+        }
+
+        #[test]
+        fn play_card_and_return_new_rejects_held_card_that_is_unplayable() {
+            let mut game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+            game_state.players[0].hand = vec![
+                card(SuitEnum::Club, NumberEnum::Seven),
+                card(SuitEnum::Heart, NumberEnum::Seven),
+                card(SuitEnum::Heart, NumberEnum::Nine),
+            ];
+            let output = game_state.play_card_and_return_new(Card {
+                suit: SuitEnum::Heart,
+                number: NumberEnum::Nine,
+            });
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                GameStateError::UnplayableCard.to_string()
+            );
+        }
+
+        fn partnership_game(players: usize, partnership: PartnershipWin) -> GameState {
+            let rules = RuleSet::builder().partnership(partnership).build().unwrap();
+            GameState::new(players, rules, players as u8 - 1).unwrap()
+        }
+
+        #[test]
+        fn partners_sit_opposite() {
+            let game_state = partnership_game(6, PartnershipWin::FirstPartnerOut);
+
+            let teams: Vec<u8> = (0..6).map(|seat| game_state.team(seat)).collect();
+            assert_eq!(teams, vec![0, 1, 2, 0, 1, 2]);
+        }
+
+        #[test]
+        fn partnerships_need_an_even_table() {
+            let rules = RuleSet::builder()
+                .partnership(PartnershipWin::FirstPartnerOut)
+                .build()
+                .unwrap();
+
+            let output = GameState::new(5, rules, 4);
+
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                GameStateError::UnevenPartnerships(5).to_string()
+            );
+        }
+
+        #[test]
+        fn team_wins_when_first_partner_is_out() {
+            let mut game_state = partnership_game(4, PartnershipWin::FirstPartnerOut);
+            assert_eq!(game_state.winning_seat(), None);
+
+            game_state.players[2].hand.clear();
+
+            assert_eq!(game_state.winning_seat(), Some(2));
+            assert_eq!(game_state.team(2), game_state.team(0));
+        }
+
+        #[test]
+        fn team_wins_when_both_partners_are_out() {
+            let mut game_state = partnership_game(4, PartnershipWin::BothPartnersOut);
+            game_state.players[1].hand.clear();
+            assert_eq!(game_state.winning_seat(), None);
+
+            game_state.players[3].hand.clear();
+
+            assert_eq!(game_state.winning_seat(), Some(1));
+        }
+
+        #[test]
+        fn pass_turn_skips_players_who_are_out() {
+            let mut game_state = partnership_game(4, PartnershipWin::BothPartnersOut);
+            game_state.players[1].hand.clear();
+
+            game_state.pass_turn().unwrap();
+
+            assert_eq!(game_state.player_turn, 2);
+        }
+
+        #[test]
+        fn partners_do_not_pay_each_other() {
+            let rules = RuleSet::builder()
+                .partnership(PartnershipWin::FirstPartnerOut)
+                .chips(ChipRule {
+                    ante: 0,
+                    knock_fee: 0,
+                    per_card: 3,
+                })
+                .build()
+                .unwrap();
+            let mut game_state = GameState::new(4, rules, 3).unwrap();
+            game_state.players[0].hand.clear();
+            game_state.players[1].hand = vec![card(SuitEnum::Club, NumberEnum::Two)];
+            game_state.players[2].hand = vec![card(SuitEnum::Club, NumberEnum::Three)];
+            game_state.players[3].hand = vec![card(SuitEnum::Club, NumberEnum::Four)];
+
+            assert_eq!(game_state.settle(0), vec![6, -3, 0, -3]);
+        }
+
+        #[test]
+        fn knock_gives_a_card_to_the_next_player_in() {
+            let rules = RuleSet::builder()
+                .knock_penalty(KnockPenalty::GiveToNext)
+                .build()
+                .unwrap();
+            let mut game_state = GameState::new(3, rules, 2).unwrap();
+            let two_of_clubs = card(SuitEnum::Club, NumberEnum::Two);
+            game_state.players[0].hand = vec![
+                two_of_clubs.clone(),
+                card(SuitEnum::Club, NumberEnum::Three),
+            ];
+            game_state.players[1].hand.clear();
+
+            assert_eq!(game_state.get_knock_transfers().len(), 2);
+            assert!(game_state
+                .knock_with_transfer(card(SuitEnum::Heart, NumberEnum::Two))
+                .is_err());
+            game_state
+                .knock_with_transfer(two_of_clubs.clone())
+                .unwrap();
+
+            assert_eq!(game_state.players[0].hand.len(), 1);
+            assert!(game_state.players[2].hand.contains(&two_of_clubs));
+            assert_eq!(game_state.player_turn, 2);
+        }
+
+        #[test]
+        fn playing_a_centre_card_earns_a_bonus_turn() {
+            let rules = RuleSet::builder()
+                .bonus_turn(BonusTurn::Centre)
+                .build()
+                .unwrap();
+            let mut game_state = GameState::new(3, rules, 2).unwrap();
+            let seven_of_clubs = card(SuitEnum::Club, NumberEnum::Seven);
+            game_state.players[0].hand = vec![
+                seven_of_clubs.clone(),
+                card(SuitEnum::Club, NumberEnum::Eight),
+                card(SuitEnum::Club, NumberEnum::Two),
+            ];
+
+            game_state.play_card(seven_of_clubs).unwrap();
+            assert_eq!(game_state.player_turn, 0);
+            assert_eq!(game_state.turns, 1);
+
+            game_state
+                .play_card(card(SuitEnum::Club, NumberEnum::Eight))
+                .unwrap();
+            assert_eq!(game_state.player_turn, 1);
+        }
+
+        #[test]
+        fn knock_never_passes_a_players_last_card() {
+            let rules = RuleSet::builder()
+                .knock_penalty(KnockPenalty::GiveToNext)
+                .build()
+                .unwrap();
+            let mut game_state = GameState::new(3, rules, 2).unwrap();
+            game_state.players[0].hand = vec![card(SuitEnum::Club, NumberEnum::Two)];
+
+            assert!(game_state.get_knock_transfers().is_empty());
+            assert_eq!(game_state.knock_options().unwrap().len(), 1);
+        }
+
+        fn hand_sizes(dealing: DealingScheme, dealer: u8) -> Vec<usize> {
+            let rules = RuleSet::builder().dealing(dealing).build().unwrap();
+            let game_state = GameState::new(5, rules, dealer).unwrap();
+            game_state
+                .players
+                .iter()
+                .map(|player| player.hand.len())
+                .collect()
+        }
+
+        #[test]
+        fn dealing_from_dealers_left_gives_them_the_extras() {
+            assert_eq!(
+                hand_sizes(DealingScheme::FromDealersLeft, 1),
+                vec![10, 10, 11, 11, 10]
+            );
+        }
+
+        #[test]
+        fn dealing_in_packets() {
+            assert_eq!(
+                hand_sizes(DealingScheme::Packets(3), 4),
+                vec![12, 12, 10, 9, 9]
+            );
+        }
+
+        #[test]
+        fn widow_holds_the_extras() {
+            let rules = RuleSet::builder()
+                .dealing(DealingScheme::Widow)
+                .build()
+                .unwrap();
+            let game_state = GameState::new(5, rules, 4).unwrap();
+
+            // a seven among the extras is laid straight away
+            let laid = 2 - game_state.widow.len();
+            assert_eq!(game_state.game_board.is_empty(), laid == 0);
+            for player in game_state.players.iter() {
+                assert_eq!(player.hand.len(), 10);
+            }
+        }
+
+        /// Rules dealing two of five cards to each of two players, leaving one as the widow.
+        fn widow_of_one(opening: OpeningRule) -> RuleSet {
+            RuleSet::builder()
+                .deck(custom_deck("7D,7H,7S,8D,8H"))
+                .dealing(DealingScheme::Widow)
+                .opening(opening)
+                .build()
+                .unwrap()
+        }
+
+        /// A two player `widow_of_one` deal of the given hands and widow, dealt by the second
+        /// player.
+        fn widow_deal(opening: OpeningRule, hands: [&str; 2], widow: &str) -> GameState {
+            let rules = widow_of_one(opening);
+            let mut game_state = GameState::new(2, rules.clone(), 1).unwrap();
+            game_state.game_board = GameBoard::new(&rules).unwrap();
+            for (player, hand) in game_state.players.iter_mut().zip(hands) {
+                player.hand = cards(hand);
+            }
+            game_state.widow = cards(widow);
+            game_state.open().unwrap();
+            game_state
+        }
+
+        #[test]
+        fn seven_in_the_widow_is_laid_at_the_deal() {
+            let game_state = widow_deal(OpeningRule::default(), ["7D,8D", "7S,8H"], "7H");
+
+            assert!(game_state.widow.is_empty());
+            assert_eq!(game_state.game_board.centre(), Some(NumberEnum::Seven));
+            assert!(!game_state.game_board.is_empty());
+            assert_eq!(game_state.player_turn, 0);
+        }
+
+        #[test]
+        fn widow_without_a_seven_is_kept() {
+            let game_state = widow_deal(OpeningRule::default(), ["7D,7H", "7S,8H"], "8D");
+
+            assert_eq!(game_state.widow, cards("8D"));
+            assert!(game_state.game_board.is_empty());
+            assert_eq!(game_state.player_turn, 0);
+        }
+
+        #[test]
+        fn opening_card_in_the_widow_opens_the_game() {
+            let opening = OpeningRule::new(seven_of_diamonds(), FirstPlayer::HolderOfOpeningCard);
+
+            let game_state = widow_deal(opening, ["7H,8D", "7S,8H"], "7D");
+
+            // laid at the deal, leaving the player on the dealer's left to lead
+            assert!(game_state.widow.is_empty());
+            assert!(!game_state.game_board.is_empty());
+            assert_eq!(game_state.player_turn, 0);
+        }
+
+        #[test]
+        fn held_opening_card_leads_despite_the_widow() {
+            let opening = OpeningRule::new(seven_of_diamonds(), FirstPlayer::HolderOfOpeningCard);
+
+            let game_state = widow_deal(opening, ["7H,8H", "7D,7S"], "8D");
+
+            assert_eq!(game_state.widow, cards("8D"));
+            assert!(game_state.game_board.is_empty());
+            assert_eq!(game_state.player_turn, 1);
+        }
+
+        #[test]
+        fn extras_go_to_the_leader() {
+            assert_eq!(
+                hand_sizes(DealingScheme::ExtrasToLeader, 4),
+                vec![12, 10, 10, 10, 10]
+            );
+            let rules = RuleSet::builder()
+                .dealing(DealingScheme::ExtrasToLeader)
+                .opening(OpeningRule::new(
+                    seven_of_diamonds(),
+                    FirstPlayer::HolderOfOpeningCard,
+                ))
+                .build()
+                .unwrap();
+            let game_state = GameState::new(5, rules, 4).unwrap();
+
+            assert_eq!(
+                game_state.players[game_state.player_turn as usize]
+                    .hand
+                    .len(),
+                12
+            );
+        }
+
+        #[test]
+        fn widow_cards_are_laid_once_playable() {
+            let mut game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+            game_state.widow = vec![
+                card(SuitEnum::Club, NumberEnum::Nine),
+                card(SuitEnum::Club, NumberEnum::Eight),
+            ];
+            game_state.players[0].hand = vec![
+                card(SuitEnum::Club, NumberEnum::Seven),
+                card(SuitEnum::Heart, NumberEnum::King),
+            ];
+
+            game_state
+                .play_card(card(SuitEnum::Club, NumberEnum::Seven))
+                .unwrap();
+
+            assert!(game_state.widow.is_empty());
+            let playable = game_state.game_board.get_playable_cards().unwrap().unwrap();
+            assert!(playable.contains(&card(SuitEnum::Club, NumberEnum::Ten)));
+        }
+
+        /// A three player game with the seven of clubs on the table, where the first player
+        /// holds a joker and the second holds the eight of clubs.
+        fn game_with_joker(replacement: WildReplacement) -> GameState {
+            let rules = RuleSet::builder()
+                .wilds(WildRule {
+                    jokers: 1,
+                    wild_number: None,
+                    replacement,
+                })
+                .build()
+                .unwrap();
+            let mut game_state = GameState::new(3, rules, 2).unwrap();
+            game_state
+                .game_board
+                .play_card(card(SuitEnum::Club, NumberEnum::Seven))
+                .unwrap();
+            game_state.players[0].hand =
+                vec![Card::joker(), card(SuitEnum::Heart, NumberEnum::King)];
+            game_state.players[1].hand = vec![
+                card(SuitEnum::Club, NumberEnum::Eight),
+                card(SuitEnum::Diamond, NumberEnum::Two),
+            ];
+            game_state
+        }
+
+        #[test]
+        fn joker_can_stand_in_at_either_end_of_an_open_row() {
+            let game_state = game_with_joker(WildReplacement::Reclaimed);
+
+            let wild_plays = game_state.get_wild_plays().unwrap();
+
+            assert_eq!(wild_plays.len(), 2);
+            assert!(wild_plays.contains(&(Card::joker(), card(SuitEnum::Club, NumberEnum::Six))));
+            assert!(wild_plays.contains(&(Card::joker(), card(SuitEnum::Club, NumberEnum::Eight))));
+        }
+
+        #[test]
+        fn real_card_reclaims_the_joker() {
+            let mut game_state = game_with_joker(WildReplacement::Reclaimed);
+            game_state
+                .play_wild(Card::joker(), card(SuitEnum::Club, NumberEnum::Eight))
+                .unwrap();
+            assert_eq!(
+                game_state.players[0].hand,
+                vec![card(SuitEnum::Heart, NumberEnum::King)]
+            );
+            assert_eq!(game_state.player_turn, 1);
+
+            game_state
+                .play_card(card(SuitEnum::Club, NumberEnum::Eight))
+                .unwrap();
+
+            assert_eq!(
+                game_state.players[1].hand,
+                vec![card(SuitEnum::Diamond, NumberEnum::Two), Card::joker()]
+            );
+        }
+
+        #[test]
+        fn joker_left_on_table_is_not_reclaimed() {
+            let mut game_state = game_with_joker(WildReplacement::LeftOnTable);
+            game_state
+                .play_wild(Card::joker(), card(SuitEnum::Club, NumberEnum::Eight))
+                .unwrap();
+
+            game_state
+                .play_card(card(SuitEnum::Club, NumberEnum::Eight))
+                .unwrap();
+
+            assert_eq!(
+                game_state.players[1].hand,
+                vec![card(SuitEnum::Diamond, NumberEnum::Two)]
+            );
+        }
+
+        #[test]
+        fn play_wild_rejects_card_that_is_not_wild() {
+            let mut game_state = game_with_joker(WildReplacement::Reclaimed);
+            let king_of_hearts = card(SuitEnum::Heart, NumberEnum::King);
+
+            let output = game_state.play_wild(
+                king_of_hearts.clone(),
+                card(SuitEnum::Club, NumberEnum::Eight),
+            );
+
+            assert!(output.is_err());
+            assert_eq!(
+                output.unwrap_err().to_string(),
+                GameStateError::NotWildCard(king_of_hearts).to_string()
+            );
+        }
+    }
+}
+
+mod game_match {
+    use crate::game_state::{GameState, GameStateError};
+    use crate::rule_set::RuleSet;
+    use crate::{assess_decision, Decision};
+    use rand::Rng;
+    use std::sync::Arc;
+    use thiserror::Error;
+
+    /// How the cards left in a loser's hand are turned into penalty points.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum PenaltyScoring {
+        /// One point per card.
+        PerCard,
+        /// The rank of each card, from 1 for an Ace up to 13 for a King.
+        PipValue,
+    }
+
+    /// The outcome of one deal in a match.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct DealResult {
+        pub dealer: u8,
+        /// `None` if the deal ended in a stalemate.
+        pub winner: Option<u8>,
+        pub penalties: Vec<u32>,
+        /// Number of turns the deal lasted.
+        pub turns: u32,
+    }
+
+    #[derive(Debug, Error)]
+    pub enum MatchError {
+        #[error("GameState Error: {0}")]
+        GameStateError(#[from] GameStateError),
+
+        #[error("Decision offered no states to choose from")]
+        NoChoices,
+    }
+
+    /// A series of deals played until someone's penalty points reach the target score. The
+    /// deal passes to the left after every hand, and players choose their moves at random.
+    #[derive(Debug, Clone)]
+    pub struct Match {
+        rules: Arc<RuleSet>,
+        number_of_players: usize,
+        pub scoring: PenaltyScoring,
+        pub target_score: u32,
+        pub dealer: u8,
+        pub scores: Vec<u32>,
+        pub deals: Vec<DealResult>,
+    }
+
+    impl Match {
+        /// Creates a match where the last seat deals first.
+        pub fn new(
+            number_of_players: usize,
+            rules: impl Into<Arc<RuleSet>>,
+            scoring: PenaltyScoring,
+            target_score: u32,
+        ) -> Match {
+            Match {
+                rules: rules.into(),
+                number_of_players,
+                scoring,
+                target_score,
+                dealer: number_of_players.saturating_sub(1) as u8,
+                scores: vec![0; number_of_players],
+                deals: Vec::new(),
+            }
+        }
+
+        /// Returns true once any player has reached the target score.
+        pub fn is_over(&self) -> bool {
+            self.scores.iter().any(|score| *score >= self.target_score)
+        }
+
+        /// Returns the seat with the fewest penalty points, the earliest seat breaking ties.
+        pub fn leader(&self) -> u8 {
+            let mut leader: usize = 0;
+            for (seat, score) in self.scores.iter().enumerate() {
+                if *score < self.scores[leader] {
+                    leader = seat;
+                }
+            }
+            leader as u8
+        }
+
+        /// Plays one deal, adds its penalties to the scores and passes the deal on.
+        pub fn play_deal<R: Rng>(&mut self, rng: &mut R) -> Result<DealResult, MatchError> {
+            let initial = GameState::new(self.number_of_players, self.rules.clone(), self.dealer)?;
+            let (winner, final_state) = play_out_randomly(initial, rng)?;
+            let penalties: Vec<u32> = final_state
+                .players
+                .iter()
+                .map(|player| self.penalty(player.hand.iter().map(|card| card.number.rank())))
+                .collect();
+            for (seat, penalty) in penalties.iter().enumerate() {
+                self.scores[seat] += penalty;
+            }
+            let result = DealResult {
+                dealer: self.dealer,
+                winner,
+                penalties,
+                turns: final_state.turns,
+            };
+            self.deals.push(result.clone());
+            self.dealer = ((self.dealer as usize + 1) % self.number_of_players) as u8;
+            Ok(result)
+        }
+
+        /// Plays deals until the match is over and returns the winning seat.
+        pub fn play<R: Rng>(&mut self, rng: &mut R) -> Result<u8, MatchError> {
+            while !self.is_over() {
+                self.play_deal(rng)?;
+            }
+            Ok(self.leader())
+        }
+
+        fn penalty(&self, ranks: impl Iterator<Item = u8>) -> u32 {
+            match self.scoring {
+                PenaltyScoring::PerCard => ranks.count() as u32,
+                PenaltyScoring::PipValue => ranks.map(|rank| rank as u32).sum(),
+            }
+        }
+    }
+
+    /// Plays a deal to the end, choosing uniformly at random at every decision. Returns the
+    /// winner, if any, and the state the game ended in.
+    fn play_out_randomly<R: Rng>(
+        initial: GameState,
+        rng: &mut R,
+    ) -> Result<(Option<u8>, GameState), MatchError> {
+        let mut state = initial;
+        loop {
+            match assess_decision(state.clone())? {
+                Decision::Victory(winner, _) => return Ok((Some(winner), state)),
+                Decision::Stalemate => return Ok((None, state)),
+                Decision::Placings(placings, _) => return Ok((Some(placings[0]), state)),
+                Decision::NoPlayableCards(next) => state = next,
+                Decision::OnePlayableCard(next) => state = next,
+                Decision::MultiplePlayableCards(mut states) | Decision::Draw(mut states) => {
+                    if states.is_empty() {
+                        return Err(MatchError::NoChoices);
+                    }
+                    let choice = rng.gen_range(0..states.len());
+                    state = states.swap_remove(choice);
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        #[test]
+        fn initialization() {
+            let game_match = Match::new(4, RuleSet::default(), PenaltyScoring::PerCard, 100);
+
+            assert_eq!(game_match.scores, vec![0, 0, 0, 0]);
+            assert_eq!(game_match.dealer, 3);
+            assert!(!game_match.is_over());
+        }
+
+        #[test]
+        fn deal_scores_cards_left_and_rotates_dealer() {
+            let mut rng = StdRng::seed_from_u64(7);
+            let mut game_match = Match::new(4, RuleSet::default(), PenaltyScoring::PerCard, 100);
+
+            let result = game_match.play_deal(&mut rng).unwrap();
+
+            let winner = result.winner.unwrap();
+            assert_eq!(result.dealer, 3);
+            assert_eq!(result.penalties[winner as usize], 0);
+            assert!(result.penalties.iter().sum::<u32>() > 0);
+            assert_eq!(game_match.scores, result.penalties);
+            assert_eq!(game_match.dealer, 0);
+        }
+
+        #[test]
+        fn pip_value_scores_card_ranks() {
+            let game_match = Match::new(4, RuleSet::default(), PenaltyScoring::PipValue, 100);
+
+            assert_eq!(game_match.penalty([1, 12, 13].into_iter()), 26);
+        }
+
+        #[test]
+        fn match_plays_until_target_score() {
+            let mut rng = StdRng::seed_from_u64(11);
+            let mut game_match = Match::new(3, RuleSet::default(), PenaltyScoring::PerCard, 30);
+
+            let winner = game_match.play(&mut rng).unwrap();
+
+            assert!(game_match.is_over());
+            assert!(!game_match.deals.is_empty());
+            assert_eq!(winner, game_match.leader());
+            let total: u32 = game_match
+                .deals
+                .iter()
+                .map(|deal| deal.penalties[winner as usize])
+                .sum();
+            assert_eq!(game_match.scores[winner as usize], total);
+            // the deal passes round the table
+            for (index, deal) in game_match.deals.iter().enumerate() {
+                assert_eq!(deal.dealer as usize, (index + 2) % 3);
+            }
+        }
+    }
+}
+
+mod search {
+    use crate::game_state::{GameState, GameStateError};
+    use crate::{assess_decision, process_branches, Decision};
+    use std::collections::{HashMap, VecDeque};
+    use std::mem::size_of;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::thread;
+
+    /// How the options of a player's choice are weighted against each other.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Weighting {
+        /// Every option keeps the weight of the choice, so each finished game counts once.
+        Leaves,
+        /// Each of k options is weighted 1/k, so each finished game is weighted by its
+        /// probability when every choice is made uniformly at random.
+        UniformPlay,
+    }
+
+    impl Weighting {
+        /// Returns the weight of each of `options` options of a choice weighted `weight`.
+        pub fn choice(&self, weight: f64, options: usize) -> f64 {
+            match self {
+                Weighting::Leaves => weight,
+                Weighting::UniformPlay => weight / options as f64,
+            }
+        }
+    }
+
+    /// The finished games below a position. Each game is weighted by the chance of the draws
+    /// leading to it, and by the `Weighting` of the choices leading to it.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Tally {
+        /// Games won by each seat.
+        pub victories: Vec<f64>,
+        pub stalemates: f64,
+        /// Turns taken from the position to the end of each game, added up.
+        pub turns: f64,
+        /// Net chips of each seat at the end of each game won, added up.
+        pub chips: Vec<f64>,
+        /// `placings[seat][place]` counts the games where the seat finished in that place.
+        pub placings: Vec<Vec<f64>>,
+    }
+
+    impl Tally {
+        pub fn new(players: usize) -> Tally {
+            Tally {
+                victories: vec![0.0; players],
+                stalemates: 0.0,
+                turns: 0.0,
+                chips: vec![0.0; players],
+                placings: vec![vec![0.0; players]; players],
+            }
+        }
+
+        /// Returns the number of finished games, counting stalemates.
+        pub fn games(&self) -> f64 {
+            self.victories.iter().sum::<f64>() + self.stalemates
+        }
+
+        /// Counts the game `decision` ends, `turns` turns from the position, returning false
+        /// if the game is not over.
+        pub fn record(&mut self, decision: &Decision, turns: f64, weight: f64) -> bool {
+            match decision {
+                Decision::Victory(winner, settlement) => {
+                    self.victories[*winner as usize] += weight;
+                    self.add_chips(settlement, weight);
+                }
+                Decision::Placings(placings, settlement) => {
+                    self.victories[placings[0] as usize] += weight;
+                    for (place, seat) in placings.iter().enumerate() {
+                        self.placings[*seat as usize][place] += weight;
+                    }
+                    self.add_chips(settlement, weight);
+                }
+                Decision::Stalemate => self.stalemates += weight,
+                _ => return false,
+            }
+            self.turns += turns * weight;
+            true
+        }
+
+        /// Adds the tally of a position `turns` turns further on, with each of its games
+        /// weighted by `weight`.
+        pub fn add(&mut self, other: &Tally, turns: f64, weight: f64) {
+            for seat in 0..self.victories.len() {
+                self.victories[seat] += other.victories[seat] * weight;
+                self.chips[seat] += other.chips[seat] * weight;
+                for place in 0..self.placings[seat].len() {
+                    self.placings[seat][place] += other.placings[seat][place] * weight;
+                }
+            }
+            self.stalemates += other.stalemates * weight;
+            self.turns += (other.turns + turns * other.games()) * weight;
+        }
+
+        fn add_chips(&mut self, settlement: &[i32], weight: f64) {
+            for (seat, chips) in settlement.iter().enumerate() {
+                self.chips[seat] += *chips as f64 * weight;
+            }
+        }
+    }
+
+    /// A value stored in a `TranspositionTable`.
+    pub trait TableEntry {
+        /// Returns the approximate memory the value holds outside itself, in bytes.
+        fn heap_size(&self) -> usize;
+    }
+
+    impl TableEntry for Tally {
+        fn heap_size(&self) -> usize {
+            let players = self.victories.len();
+            (2 * players + players * players) * size_of::<f64>() + players * size_of::<Vec<f64>>()
+        }
+    }
+
+    /// Solved positions, keyed by `GameState::key`. Once the table holds its capacity,
+    /// newly solved positions are no longer stored.
+    #[derive(Debug)]
+    pub struct TranspositionTable<T: TableEntry = Tally> {
+        entries: HashMap<Vec<u8>, T>,
+        /// Approximate memory used by the entries, in bytes.
+        size: usize,
+        capacity: usize,
+        /// Number of times a position was found already solved.
+        pub hits: u64,
+    }
+
+    impl<T: TableEntry> TranspositionTable<T> {
+        /// Creates a table using at most about `capacity` bytes.
+        pub fn new(capacity: usize) -> TranspositionTable<T> {
+            TranspositionTable {
+                entries: HashMap::new(),
+                size: 0,
+                capacity,
+                hits: 0,
+            }
+        }
+
+        /// Returns the number of positions stored.
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        pub fn get(&mut self, key: &[u8]) -> Option<&T> {
+            let output = self.entries.get(key);
+            if output.is_some() {
+                self.hits += 1;
+            }
+            output
+        }
+
+        /// Stores `value` for the position `key`, if there is room for it. A position already
+        /// stored is overwritten.
+        pub fn insert(&mut self, key: Vec<u8>, value: T) {
+            if let Some(stored) = self.entries.get_mut(&key) {
+                *stored = value;
+                return;
+            }
+            // the key, the value, and the map's own bookkeeping
+            let entry_size = key.len()
+                + size_of::<Vec<u8>>()
+                + size_of::<T>()
+                + value.heap_size()
+                + size_of::<u64>();
+            if self.size + entry_size > self.capacity {
+                return;
+            }
+            self.size += entry_size;
+            self.entries.insert(key, value);
+        }
+    }
+
+    /// Tallies every game from `initial`, exploring the tree depth first.
+    pub fn explore(initial: GameState, weighting: Weighting) -> Result<Tally, String> {
+        let mut tally = Tally::new(initial.players.len());
+        let mut branches: Vec<(GameState, f64)> = Vec::new();
+        let mut game_state: Option<(GameState, f64)> = Some((initial, 1.0));
+        while let Some((state, weight)) = game_state.take() {
+            let turns = state.turns as f64;
+            match assess_decision(state) {
+                // a finished game ends this branch, so resume from the next unexplored one
+                Ok(decision) => match tally.record(&decision, turns, weight) {
+                    true => game_state = branches.pop(),
+                    false => {
+                        game_state = Some(process_branches(
+                            &mut branches,
+                            decision,
+                            weight,
+                            weighting,
+                        )?)
+                    }
+                },
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+        Ok(tally)
+    }
+
+    /// Work shared between the threads of a parallel exploration.
+    struct SharedWork {
+        /// Unexplored states and their weights, one queue for each worker.
+        queues: Vec<Mutex<VecDeque<(GameState, f64)>>>,
+        /// States queued or being explored. The search is over once none are left.
+        pending: AtomicUsize,
+        failure: Mutex<Option<String>>,
+        failed: AtomicBool,
+        weighting: Weighting,
+    }
+
+    /// Tallies every game from `initial`, sharing the tree between `threads` worker threads.
+    /// Each worker explores depth first from the back of its own queue. A worker that runs
+    /// out of work steals from the front of another's queue, where the states nearest the
+    /// root, and so usually with the largest subtrees, are waiting.
+    pub fn explore_parallel(
+        initial: GameState,
+        threads: usize,
+        weighting: Weighting,
+    ) -> Result<Tally, String> {
+        let players = initial.players.len();
+        let threads = threads.max(1);
+        let work = SharedWork {
+            queues: (0..threads).map(|_| Mutex::new(VecDeque::new())).collect(),
+            pending: AtomicUsize::new(1),
+            failure: Mutex::new(None),
+            failed: AtomicBool::new(false),
+            weighting,
+        };
+        lock(&work.queues[0]).push_back((initial, 1.0));
+        let tallies: Vec<thread::Result<Tally>> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|worker| {
+                    let work = &work;
+                    scope.spawn(move || explore_queue(worker, work, players))
+                })
+                .collect();
+            workers.into_iter().map(|worker| worker.join()).collect()
+        });
+        if let Some(e) = lock(&work.failure).take() {
+            return Err(e);
+        }
+        let mut output = Tally::new(players);
+        for tally in tallies {
+            match tally {
+                Ok(tally) => output.add(&tally, 0.0, 1.0),
+                Err(_) => return Err("An exploring thread panicked".to_string()),
+            }
+        }
+        Ok(output)
+    }
+
+    /// Explores states from `worker`'s queue, stealing from the other queues when it is
+    /// empty, until no work is left anywhere.
+    fn explore_queue(worker: usize, work: &SharedWork, players: usize) -> Tally {
+        let mut tally = Tally::new(players);
+        while !work.failed.load(Ordering::SeqCst) {
+            // the worker's own queue is unlocked before any other is locked
+            let own = lock(&work.queues[worker]).pop_back();
+            let next = own.or_else(|| {
+                (1..work.queues.len())
+                    .map(|offset| (worker + offset) % work.queues.len())
+                    .find_map(|victim| lock(&work.queues[victim]).pop_front())
+            });
+            let (state, weight) = match next {
+                Some(item) => item,
+                None if work.pending.load(Ordering::SeqCst) == 0 => break,
+                None => {
+                    thread::yield_now();
+                    continue;
+                }
+            };
+            let turns = state.turns as f64;
+            let children = assess_decision(state)
+                .map_err(|e| e.to_string())
+                .and_then(|decision| match tally.record(&decision, turns, weight) {
+                    true => Ok(Vec::new()),
+                    false => {
+                        let mut children = Vec::new();
+                        let next =
+                            process_branches(&mut children, decision, weight, work.weighting)?;
+                        children.push(next);
+                        Ok(children)
+                    }
+                });
+            match children {
+                Ok(children) => {
+                    // children are counted before their parent is finished, so pending never
+                    // reaches zero while any work remains
+                    work.pending.fetch_add(children.len(), Ordering::SeqCst);
+                    lock(&work.queues[worker]).extend(children);
+                    work.pending.fetch_sub(1, Ordering::SeqCst);
+                }
+                Err(e) => {
+                    lock(&work.failure).get_or_insert(e);
+                    work.failed.store(true, Ordering::SeqCst);
+                }
+            }
+        }
+        tally
+    }
+
+    /// Locks `mutex`, carrying on with its data even if another thread panicked holding it.
+    fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+        mutex
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Tallies every game from `state`, exploring each position only once: positions
+    /// reached again by a different order of moves are taken from `table`.
+    pub fn solve(
+        state: GameState,
+        table: &mut TranspositionTable,
+        weighting: Weighting,
+    ) -> Result<Tally, GameStateError> {
+        let key = state.key();
+        if let Some(tally) = table.get(&key) {
+            return Ok(tally.clone());
+        }
+        let turns = state.turns;
+        let mut tally = Tally::new(state.players.len());
+        let decision = assess_decision(state)?;
+        if !tally.record(&decision, 0.0, 1.0) {
+            let (children, weight) = match decision {
+                Decision::NoPlayableCards(next) | Decision::OnePlayableCard(next) => {
+                    (vec![next], 1.0)
+                }
+                Decision::MultiplePlayableCards(states) => {
+                    let weight = weighting.choice(1.0, states.len());
+                    (states, weight)
+                }
+                // every card that could be drawn is equally likely
+                Decision::Draw(states) => {
+                    let weight = 1.0 / states.len() as f64;
+                    (states, weight)
+                }
+                Decision::Victory(_, _) | Decision::Placings(_, _) | Decision::Stalemate => {
+                    (Vec::new(), 1.0)
+                }
+            };
+            for child in children {
+                let child_turns = (child.turns - turns) as f64;
+                let child_tally = solve(child, table, weighting)?;
+                tally.add(&child_tally, child_turns, weight);
+            }
+        }
+        table.insert(key, tally.clone());
+        Ok(tally)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::rule_set::RuleSet;
+        use crate::test_helpers::custom_deck;
+
+        fn small_deal(players: usize) -> GameState {
+            let rules = RuleSet::builder()
+                .deck(custom_deck("5H,6H,7H,8H,9H,5S,6S,7S,8S,9S"))
+                .build()
+                .unwrap();
+            GameState::new(players, rules, players as u8 - 1).unwrap()
+        }
+
+        #[test]
+        fn solving_with_a_table_matches_full_exploration() {
+            for players in [2, 3] {
+                let initial = small_deal(players);
+                let mut table = TranspositionTable::new(1024 * 1024);
+
+                let solved = solve(initial.clone(), &mut table, Weighting::Leaves).unwrap();
+                let explored = explore(initial, Weighting::Leaves).unwrap();
+
+                assert_eq!(solved.victories, explored.victories);
+                assert_eq!(solved.stalemates, explored.stalemates);
+                assert_eq!(solved.turns, explored.turns);
+            }
+        }
+
+        #[test]
+        fn parallel_exploration_matches_single_threaded() {
+            for players in [2, 3] {
+                let initial = small_deal(players);
+
+                let parallel = explore_parallel(initial.clone(), 4, Weighting::Leaves).unwrap();
+                let explored = explore(initial, Weighting::Leaves).unwrap();
+
+                assert_eq!(parallel.victories, explored.victories);
+                assert_eq!(parallel.stalemates, explored.stalemates);
+                assert_eq!(parallel.turns, explored.turns);
+            }
+        }
+
+        #[test]
+        fn uniform_play_gives_probabilities() {
+            for players in [2, 3] {
+                let initial = small_deal(players);
+                let mut table = TranspositionTable::new(1024 * 1024);
+
+                let explored = explore(initial.clone(), Weighting::UniformPlay).unwrap();
+                let solved = solve(initial, &mut table, Weighting::UniformPlay).unwrap();
+
+                assert!((explored.games() - 1.0).abs() < 1e-9);
+                for seat in 0..players {
+                    assert!((explored.victories[seat] - solved.victories[seat]).abs() < 1e-9);
+                }
+            }
+        }
+
+        #[test]
+        fn full_table_stores_nothing_more() {
+            let mut table = TranspositionTable::new(0);
+
+            solve(small_deal(2), &mut table, Weighting::Leaves).unwrap();
+
+            assert_eq!(table.len(), 0);
+        }
+    }
+}
+
+mod max_n {
+    use crate::card_and_enums::Card;
+    use crate::game_state::{GameState, GameStateError};
+    use crate::{assess_decision, Decision};
+
+    /// How the players other than the one to move at the root are assumed to play.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum SearchVariant {
+        /// Every player makes the move best for themselves, or for their team.
+        MaxN,
+        /// Every opponent of the root player works against them.
+        Paranoid,
+        /// Between two of the root player's turns, only the opponent whose reply hurts the
+        /// root player most chooses freely. The other opponents make their first legal move.
+        BestReply,
+    }
+
+    /// The result of searching a deal.
+    #[derive(Debug, Clone)]
+    pub struct Solution {
+        /// What each seat achieves: 1 for a win and 0 for a loss, with placings in between.
+        /// Draws from the stock are averaged over.
+        pub outcome: Vec<f64>,
+        /// The seat that goes out first, unless the game ends in a stalemate or depends on a
+        /// draw.
+        pub winner: Option<u8>,
+        /// The states the game passes through when everyone plays as assumed. The line stops
+        /// at the first draw from the stock.
+        pub principal_variation: Vec<GameState>,
+    }
+
+    /// Searches every line of play from `state`, backing up the outcome of each game through
+    /// the choices made by the players, as `variant` assumes they play.
+    pub fn search(state: GameState, variant: SearchVariant) -> Result<Solution, GameStateError> {
+        let root = state.player_turn;
+        search_from(state, variant, root, false)
+    }
+
+    /// Searches from `state`. Under best-reply search, `replied` is true once an opponent has
+    /// chosen freely since the root player's last turn.
+    fn search_from(
+        state: GameState,
+        variant: SearchVariant,
+        root: u8,
+        replied: bool,
+    ) -> Result<Solution, GameStateError> {
+        let players = state.players.len();
+        let teams: Vec<u8> = (0..players).map(|seat| state.team(seat as u8)).collect();
+        let mover = state.player_turn;
+        let for_root = teams[mover as usize] == teams[root as usize];
+        let leaf = |outcome: Vec<f64>, winner: Option<u8>| Solution {
+            outcome,
+            winner,
+            principal_variation: Vec::new(),
+        };
+        let next = |child: GameState, replied: bool| {
+            // a new round of replies starts each time the root player is to move
+            let replied = replied && child.player_turn != root;
+            search_from(child, variant, root, replied)
+        };
+        let options: Vec<(GameState, Solution)> = match assess_decision(state)? {
+            Decision::Victory(winner, _) => {
+                let outcome = (0..players)
+                    .map(|seat| match teams[seat] == teams[winner as usize] {
+                        true => 1.0,
+                        false => 0.0,
+                    })
+                    .collect();
+                return Ok(leaf(outcome, Some(winner)));
+            }
+            Decision::Placings(placings, _) => {
+                let mut outcome = vec![0.0; players];
+                for (place, seat) in placings.iter().enumerate() {
+                    // a player alone at the table is first with no one to beat
+                    outcome[*seat as usize] = match players {
+                        1 => 1.0,
+                        _ => (players - 1 - place) as f64 / (players - 1) as f64,
+                    };
+                }
+                return Ok(leaf(outcome, Some(placings[0])));
+            }
+            Decision::Stalemate => return Ok(leaf(vec![0.0; players], None)),
+            Decision::Draw(states) => {
+                let mut outcome = vec![0.0; players];
+                let chance = 1.0 / states.len() as f64;
+                for child in states {
+                    let solution = next(child, replied)?;
+                    for (total, seat_outcome) in outcome.iter_mut().zip(solution.outcome) {
+                        *total += seat_outcome * chance;
+                    }
+                }
+                return Ok(leaf(outcome, None));
+            }
+            Decision::NoPlayableCards(child) | Decision::OnePlayableCard(child) => {
+                vec![(child.clone(), next(child, replied)?)]
+            }
+            Decision::MultiplePlayableCards(states) => {
+                let mut output = Vec::new();
+                for (index, child) in states.into_iter().enumerate() {
+                    let solution = match (variant, for_root, replied) {
+                        // an opponent who is not replying makes their first move only
+                        (SearchVariant::BestReply, false, true) if index > 0 => continue,
+                        (SearchVariant::BestReply, false, false) if index == 0 => {
+                            // the first move either starts this opponent's reply or is the
+                            // move they make while another opponent replies
+                            let passive = next(child.clone(), false)?;
+                            let reply = next(child.clone(), true)?;
+                            output.push((child.clone(), passive));
+                            reply
+                        }
+                        (SearchVariant::BestReply, false, false) => next(child.clone(), true)?,
+                        _ => next(child.clone(), replied)?,
+                    };
+                    output.push((child, solution));
+                }
+                output
+            }
+        };
+        // the player to move picks the option best for whoever they are assumed to play for
+        let (judge, maximise) = match variant {
+            SearchVariant::MaxN => (mover, true),
+            SearchVariant::Paranoid | SearchVariant::BestReply => (root, for_root),
+        };
+        let mut best: Option<(GameState, Solution)> = None;
+        for (child, solution) in options {
+            let value = solution.outcome[judge as usize];
+            let better = match &best {
+                None => true,
+                Some((_, current)) if maximise => value > current.outcome[judge as usize],
+                Some((_, current)) => value < current.outcome[judge as usize],
+            };
+            if better {
+                best = Some((child, solution));
+            }
+        }
+        let (child, mut solution) = match best {
+            Some(best) => best,
+            None => return Err(GameStateError::NoPlayableCard("search".to_string())),
+        };
+        solution.principal_variation.insert(0, child);
+        Ok(solution)
+    }
+
+    /// Describes the move that took the game from `before` to `after`.
+    pub fn describe_move(before: &GameState, after: &GameState) -> String {
+        let seat = before.player_turn as usize;
+        let mut lost: Vec<Card> = before.players[seat].hand.clone();
+        let mut gained: Vec<Card> = Vec::new();
+        for card in after.players[seat].hand.iter() {
+            match lost.iter().position(|held| held == card) {
+                Some(position) => {
+                    lost.remove(position);
+                }
+                None => gained.push(card.clone()),
+            }
+        }
+        let names = |cards: &Vec<Card>| -> String {
+            let names: Vec<String> = cards.iter().map(|card| card.to_string()).collect();
+            names.join(", ")
+        };
+        let held = |state: &GameState| -> usize {
+            state.players.iter().map(|player| player.hand.len()).sum()
+        };
+        let action = if lost.is_empty() && gained.is_empty() {
+            "knocks".to_string()
+        } else if held(before) == held(after) {
+            // a knock penalty moves a card between hands without playing it
+            match lost.is_empty() {
+                true => format!("knocks and takes {}", names(&gained)),
+                false => format!("knocks and gives {}", names(&lost)),
+            }
+        } else if lost.is_empty() {
+            format!("draws {}", names(&gained))
+        } else {
+            format!("plays {}", names(&lost))
+        };
+        format!("Player {} {}", seat + 1, action)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::card_and_enums::{NumberEnum, SuitEnum};
+        use crate::rule_set::{EndCondition, RuleSet};
+        use crate::test_helpers::{card, cards, custom_deck};
+
+        /// A two player game on a fresh board. The first player holds the sevens of clubs and
+        /// hearts and the eight of clubs, and the second the six and nine of clubs. The first
+        /// player wins unless they play the eight of clubs while the seven of hearts is
+        /// still in their hand.
+        fn race() -> GameState {
+            let mut game_state = GameState::new(2, RuleSet::default(), 1).unwrap();
+            game_state.players[0].hand = vec![
+                card(SuitEnum::Club, NumberEnum::Seven),
+                card(SuitEnum::Heart, NumberEnum::Seven),
+                card(SuitEnum::Club, NumberEnum::Eight),
+            ];
+            game_state.players[1].hand = vec![
+                card(SuitEnum::Club, NumberEnum::Six),
+                card(SuitEnum::Club, NumberEnum::Nine),
+            ];
+            game_state
+        }
+
+        #[test]
+        fn max_n_finds_the_winning_line() {
+            let solution = search(race(), SearchVariant::MaxN).unwrap();
+
+            assert_eq!(solution.winner, Some(0));
+            assert_eq!(solution.outcome, vec![1.0, 0.0]);
+            let line = &solution.principal_variation;
+            assert!(line[line.len() - 1].players[0].hand.is_empty());
+            assert!(describe_move(&race(), &line[0]).starts_with("Player 1 plays 7"));
+        }
+
+        #[test]
+        fn knocks_are_described() {
+            let mut game_state = race();
+            let before = game_state.clone();
+            game_state.knock().unwrap();
+
+            assert_eq!(describe_move(&before, &game_state), "Player 1 knocks");
+        }
+
+        /// The moves along a solution's principal variation from `initial`.
+        fn moves(initial: &GameState, solution: &Solution) -> Vec<String> {
+            let mut before = initial;
+            let mut output = Vec::new();
+            for after in solution.principal_variation.iter() {
+                output.push(describe_move(before, after));
+                before = after;
+            }
+            output
+        }
+
+        /// A three player game on a fresh board, where the first player goes out first only
+        /// if the others play for themselves rather than against them.
+        fn three_way() -> GameState {
+            let mut game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+            game_state.players[0].hand = cards("9S,6S,10S,7C");
+            game_state.players[1].hand = cards("5S,8C,7H,7S");
+            game_state.players[2].hand = cards("5H,6C,6H,8S");
+            game_state
+        }
+
+        #[test]
+        fn variants_choose_different_lines() {
+            let max_n = search(three_way(), SearchVariant::MaxN).unwrap();
+            let paranoid = search(three_way(), SearchVariant::Paranoid).unwrap();
+            let best_reply = search(three_way(), SearchVariant::BestReply).unwrap();
+
+            assert_eq!(max_n.outcome, vec![1.0, 0.0, 0.0]);
+            assert_eq!(paranoid.outcome[0], 0.0);
+            assert_eq!(best_reply.outcome[0], 0.0);
+            let max_n = moves(&three_way(), &max_n);
+            let paranoid = moves(&three_way(), &paranoid);
+            let best_reply = moves(&three_way(), &best_reply);
+            // the second player only replies freely under best-reply search
+            assert_eq!(max_n[1], "Player 2 plays 7S");
+            assert_eq!(paranoid[1], "Player 2 plays 7S");
+            assert_eq!(best_reply[1], "Player 2 plays 8C");
+            // under paranoid search the third player holds back the eight of spades, which the
+            // first player needs to play their nine and ten
+            assert_eq!(max_n[2], "Player 3 plays 8S");
+            assert_eq!(paranoid[2], "Player 3 plays 6C");
+        }
+
+        /// A three player game dealt from a ten card deck, with the first player to lead.
+        fn small_three_way(hands: [&str; 3]) -> GameState {
+            let rules = RuleSet::builder()
+                .deck(custom_deck("5H,6H,7H,8H,9H,5S,6S,7S,8S,9S"))
+                .build()
+                .unwrap();
+            let mut game_state = GameState::new(3, rules, 2).unwrap();
+            for (player, hand) in game_state.players.iter_mut().zip(hands) {
+                player.hand = cards(hand);
+            }
+            game_state
+        }
+
+        #[test]
+        fn paranoid_is_the_most_pessimistic() {
+            // each deal with the first player's outcome under max-n, paranoid and best-reply
+            // search
+            let deals = [
+                (["9S,8H,7H,6H", "9H,7S,5S", "6S,8S,5H"], [1.0, 0.0, 0.0]),
+                (["9S,7S,7H,6H", "6S,8H,9H", "5H,5S,8S"], [1.0, 0.0, 0.0]),
+                (["5H,7H,8S,8H", "6S,9S,5S", "6H,7S,9H"], [1.0, 1.0, 1.0]),
+                (["5H,5S,6S,8H", "7H,8S,9H", "6H,9S,7S"], [0.0, 0.0, 0.0]),
+            ];
+            for (hands, expected) in deals {
+                let initial = small_three_way(hands);
+
+                let max_n = search(initial.clone(), SearchVariant::MaxN).unwrap();
+                let paranoid = search(initial.clone(), SearchVariant::Paranoid).unwrap();
+                let best_reply = search(initial, SearchVariant::BestReply).unwrap();
+
+                assert_eq!(
+                    [max_n.outcome[0], paranoid.outcome[0], best_reply.outcome[0]],
+                    expected
+                );
+                assert!(paranoid.outcome[0] <= max_n.outcome[0]);
+                assert!(paranoid.outcome[0] <= best_reply.outcome[0]);
+            }
+        }
+
+        #[test]
+        fn lone_player_is_placed_first() {
+            let rules = RuleSet::builder()
+                .end(EndCondition::FullOrder)
+                .build()
+                .unwrap();
+            let initial = GameState::new(1, rules, 0).unwrap();
+
+            let solution = search(initial, SearchVariant::MaxN).unwrap();
+
+            assert_eq!(solution.outcome, vec![1.0]);
+            assert_eq!(solution.winner, Some(0));
+        }
+
+        #[test]
+        fn variants_agree_for_two_players() {
+            for variant in [SearchVariant::Paranoid, SearchVariant::BestReply] {
+                let solution = search(race(), variant).unwrap();
+
+                assert_eq!(solution.winner, Some(0));
+            }
+        }
+    }
+}
+
+mod alpha_beta {
+    use crate::game_state::{GameState, GameStateError};
+    use crate::search::{TableEntry, TranspositionTable};
+    use crate::{assess_decision, Decision};
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    pub enum AlphaBetaError {
+        #[error("GameState Error: {0}")]
+        GameStateError(#[from] GameStateError),
+
+        #[error("Alpha-beta search needs exactly two players, not {0}")]
+        NotTwoPlayers(usize),
+    }
+
+    /// How a stored value relates to the true value of its position.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Bound {
+        Exact,
+        /// The true value is at least the stored one.
+        Lower,
+        /// The true value is at most the stored one.
+        Upper,
+    }
+
+    /// A position searched by `solve_two_player`, valued for the first seat.
+    #[derive(Debug, Clone)]
+    pub struct Searched {
+        value: f64,
+        bound: Bound,
+        /// The best move found, as an index into the ordered moves. It is searched first
+        /// when the position is reached again.
+        best: usize,
+    }
+
+    impl TableEntry for Searched {
+        fn heap_size(&self) -> usize {
+            0
+        }
+    }
+
+    /// The result of solving a two player position.
+    #[derive(Debug, Clone)]
+    pub struct TwoPlayerSolution {
+        /// What the player to move achieves with best play: 1 for a win, -1 for a loss and
+        /// 0 for a stalemate. Draws from the stock are averaged over.
+        pub value: f64,
+        /// The state after the player's best move, unless the game is over or the next move
+        /// is a draw from the stock.
+        pub best_move: Option<GameState>,
+    }
+
+    /// Solves a two player game from `state` by alpha-beta search. Positions are stored in
+    /// `table`, if given, and taken from it when reached again.
+    pub fn solve_two_player(
+        state: GameState,
+        mut table: Option<&mut TranspositionTable<Searched>>,
+    ) -> Result<TwoPlayerSolution, AlphaBetaError> {
+        if state.players.len() != 2 {
+            return Err(AlphaBetaError::NotTwoPlayers(state.players.len()));
+        }
+        let mover = state.player_turn;
+        let (value, best_move) = alpha_beta(state, -1.0, 1.0, &mut table, true)?;
+        Ok(TwoPlayerSolution {
+            value: match mover {
+                0 => value,
+                _ => -value,
+            },
+            best_move,
+        })
+    }
+
+    /// Searches `state` for its value to the first seat, who maximises it while the second
+    /// seat minimises it. A value at or below `alpha` is only an upper bound on the true
+    /// value, and one at or above `beta` only a lower bound. The best move is kept at the
+    /// `root` only.
+    fn alpha_beta(
+        state: GameState,
+        mut alpha: f64,
+        mut beta: f64,
+        table: &mut Option<&mut TranspositionTable<Searched>>,
+        root: bool,
+    ) -> Result<(f64, Option<GameState>), GameStateError> {
+        // the bound stored for the result is judged against the caller's window, not the one
+        // narrowed by an earlier visit
+        let (alpha_in, beta_in) = (alpha, beta);
+        let key = table.as_ref().map(|_| state.key());
+        let mut first = 0;
+        if let (Some(table), Some(key)) = (table.as_deref_mut(), &key) {
+            if let Some(entry) = table.get(key) {
+                // the root is always searched, so that its best move is known
+                if !root {
+                    match entry.bound {
+                        Bound::Exact => return Ok((entry.value, None)),
+                        Bound::Lower => alpha = alpha.max(entry.value),
+                        Bound::Upper => beta = beta.min(entry.value),
+                    }
+                    if alpha >= beta {
+                        return Ok((entry.value, None));
+                    }
+                }
+                first = entry.best;
+            }
+        }
+        let mover = state.player_turn;
+        let seat_value = |winner: u8| match winner {
+            0 => 1.0,
+            _ => -1.0,
+        };
+        let moves = match assess_decision(state)? {
+            Decision::Victory(winner, _) => return Ok((seat_value(winner), None)),
+            Decision::Placings(placings, _) => return Ok((seat_value(placings[0]), None)),
+            Decision::Stalemate => return Ok((0.0, None)),
+            Decision::Draw(states) => {
+                // every card that could be drawn is equally likely, and each is searched in
+                // full since the average depends on all of them
+                let chance = 1.0 / states.len() as f64;
+                let mut value = 0.0;
+                for child in states {
+                    value += alpha_beta(child, -1.0, 1.0, table, false)?.0 * chance;
+                }
+                store(table, key, value, Bound::Exact, 0);
+                return Ok((value, None));
+            }
+            Decision::NoPlayableCards(child) | Decision::OnePlayableCard(child) => vec![child],
+            Decision::MultiplePlayableCards(states) => order_moves(states, mover)?,
+        };
+        let mut moves: Vec<(usize, GameState)> = moves.into_iter().enumerate().collect();
+        if first < moves.len() {
+            moves[..=first].rotate_right(1);
+        }
+        let maximise = mover == 0;
+        let mut best: Option<(usize, f64, Option<GameState>)> = None;
+        for (index, child) in moves {
+            let kept = match root {
+                true => Some(child.clone()),
+                false => None,
+            };
+            let (value, _) = alpha_beta(child, alpha, beta, table, false)?;
+            let better = match &best {
+                None => true,
+                Some((_, current, _)) if maximise => value > *current,
+                Some((_, current, _)) => value < *current,
+            };
+            if better {
+                best = Some((index, value, kept));
+            }
+            match maximise {
+                true => alpha = alpha.max(value),
+                false => beta = beta.min(value),
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        let (index, value, best_move) = match best {
+            Some(best) => best,
+            None => return Err(GameStateError::NoPlayableCard("alpha-beta".to_string())),
+        };
+        let bound = if value <= alpha_in {
+            Bound::Upper
+        } else if value >= beta_in {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        store(table, key, value, bound, index);
+        Ok((value, best_move))
+    }
+
+    fn store(
+        table: &mut Option<&mut TranspositionTable<Searched>>,
+        key: Option<Vec<u8>>,
+        value: f64,
+        bound: Bound,
+        best: usize,
+    ) {
+        if let (Some(table), Some(key)) = (table.as_deref_mut(), key) {
+            let entry = Searched { value, bound, best };
+            table.insert(key, entry);
+        }
+    }
+
+    /// Orders the moves of the player `mover` so that those most likely to be best come
+    /// first: moves earning a bonus turn, then those leaving the opponent fewest cards to
+    /// play.
+    fn order_moves(states: Vec<GameState>, mover: u8) -> Result<Vec<GameState>, GameStateError> {
+        let mut scored: Vec<(usize, GameState)> = Vec::new();
+        for state in states {
+            let replies = match state.player_turn == mover {
+                true => 0,
+                false => 1 + state.get_playable_cards()?.map_or(0, |cards| cards.len()),
+            };
+            scored.push((replies, state));
+        }
+        scored.sort_by_key(|(replies, _)| *replies);
+        Ok(scored.into_iter().map(|(_, state)| state).collect())
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::max_n::{search, SearchVariant};
+        use crate::rule_set::{PlayRule, RuleSet, RuleSetBuilder, StalemateResolution};
+        use crate::test_helpers::{cards, custom_deck};
+
+        fn deal(players: usize, rules: RuleSetBuilder) -> GameState {
+            let rules = rules
+                .deck(custom_deck("4H,5H,6H,7H,8H,9H,5S,6S,7S,8S,9S,10S"))
+                .build()
+                .unwrap();
+            GameState::new(players, rules, players as u8 - 1).unwrap()
+        }
+
+        fn small_deal(players: usize) -> GameState {
+            deal(players, RuleSet::builder())
+        }
+
+        /// Values `state` for the first seat by searching every move, without pruning.
+        fn minimax(state: GameState) -> f64 {
+            let maximise = state.player_turn == 0;
+            let values: Vec<f64> = match assess_decision(state).unwrap() {
+                Decision::Victory(0, _) => return 1.0,
+                Decision::Victory(_, _) => return -1.0,
+                Decision::Stalemate => return 0.0,
+                Decision::Draw(states) => {
+                    let chance = 1.0 / states.len() as f64;
+                    return states
+                        .into_iter()
+                        .map(|child| minimax(child) * chance)
+                        .sum();
+                }
+                Decision::NoPlayableCards(child) | Decision::OnePlayableCard(child) => {
+                    vec![minimax(child)]
+                }
+                Decision::MultiplePlayableCards(states) => {
+                    states.into_iter().map(minimax).collect()
+                }
+                Decision::Placings(_, _) => unreachable!(),
+            };
+            match maximise {
+                true => values.into_iter().fold(f64::MIN, f64::max),
+                false => values.into_iter().fold(f64::MAX, f64::min),
+            }
+        }
+
+        #[test]
+        fn agrees_with_max_n() {
+            for _ in 0..20 {
+                let initial = small_deal(2);
+                let mover = initial.player_turn as usize;
+
+                let solution = solve_two_player(initial.clone(), None).unwrap();
+                let outcome = search(initial, SearchVariant::MaxN).unwrap().outcome;
+
+                assert_eq!(solution.value, outcome[mover] - outcome[1 - mover]);
+            }
+        }
+
+        #[test]
+        fn pruning_keeps_the_minimax_value() {
+            let variants = [
+                // draws from the stock are chance nodes
+                RuleSet::builder().hand_size(4),
+                // knocking with cards to play can end in a stalemate
+                RuleSet::builder().play(PlayRule::OptionalKnock(StalemateResolution::Draw)),
+            ];
+            for rules in variants {
+                for _ in 0..4 {
+                    let initial = deal(2, rules.clone());
+                    let value = match initial.player_turn {
+                        0 => minimax(initial.clone()),
+                        _ => -minimax(initial.clone()),
+                    };
+                    let mut table = TranspositionTable::new(1024 * 1024);
+
+                    let without_table = solve_two_player(initial.clone(), None).unwrap();
+                    let with_table = solve_two_player(initial, Some(&mut table)).unwrap();
+
+                    assert!((without_table.value - value).abs() < 1e-9);
+                    assert!((with_table.value - value).abs() < 1e-9);
+                }
+            }
+        }
+
+        #[test]
+        fn table_gives_the_same_value() {
+            for _ in 0..20 {
+                let initial = small_deal(2);
+                let mut table = TranspositionTable::new(1024 * 1024);
+
+                let with_table = solve_two_player(initial.clone(), Some(&mut table)).unwrap();
+                let without_table = solve_two_player(initial.clone(), None).unwrap();
+
+                assert_eq!(with_table.value, without_table.value);
+                assert!(table.len() > 0);
+                // the best move must achieve the value it was chosen for
+                let best_move = with_table.best_move.unwrap();
+                let reply = solve_two_player(best_move.clone(), None).unwrap();
+                match best_move.player_turn == initial.player_turn {
+                    true => assert_eq!(reply.value, with_table.value),
+                    false => assert_eq!(reply.value, -with_table.value),
+                }
+            }
+        }
+
+        #[test]
+        fn solves_full_hands_with_a_table() {
+            let mut initial = GameState::new(2, RuleSet::default(), 1).unwrap();
+            initial.players[0].hand = cards(
+                "7S,9S,7D,4C,8D,3C,KC,5H,JC,9C,8C,JS,KH,8S,6D,9D,QS,8H,9H,6C,QC,10H,AH,7H,AS,KD",
+            );
+            initial.players[1].hand = cards(
+                "JD,QD,10D,2D,6S,4H,5C,5D,3D,7C,10C,QH,5S,JH,4D,AD,AC,2H,3H,2S,KS,10S,4S,6H,2C,3S",
+            );
+            let mut table = TranspositionTable::new(64 * 1024 * 1024);
+
+            let solution = solve_two_player(initial, Some(&mut table)).unwrap();
+
+            assert_eq!(solution.value, 1.0);
+            // move ordering and the table keep two 26 card hands to thousands of positions
+            assert!(table.len() < 10_000);
+        }
+
+        #[test]
+        fn needs_two_players() {
+            let result = solve_two_player(small_deal(3), None);
+
+            assert!(matches!(result, Err(AlphaBetaError::NotTwoPlayers(3))));
+        }
+    }
+}
+
+mod cli {
+    use crate::card_and_enums::{Card, CardParseError, NumberEnum};
+    use crate::game_match::PenaltyScoring;
+    use crate::max_n::SearchVariant;
+    use crate::rule_set::{
+        BonusTurn, BuildingRule, CentreRule, ChipRule, DealingScheme, DeckSpec, DuplicateRows,
+        EndCondition, FirstPlayer, KnockPenalty, OpeningCard, PartnershipWin, PlayRule, RuleSet,
+        RuleSetError, SequenceRule, StalemateResolution, WildReplacement, WildRule,
+    };
+    use crate::search::Weighting;
+    use thiserror::Error;
+
+    /// Settings for a run of the explorer, read from the command line.
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Options {
+        pub players: usize,
+        /// Seat of the dealer, counting from 0. Defaults to the last seat.
+        pub dealer: Option<u8>,
+        pub rules: RuleSet,
+        /// Play a match to this many penalty points instead of exploring a single deal.
+        pub match_target: Option<u32>,
+        /// Solve the deal with a transposition table of about this many megabytes.
+        pub table_megabytes: Option<usize>,
+        /// Explore the deal on this many threads. Not used with a transposition table.
+        pub threads: Option<usize>,
+        /// Search the deal for best play instead of counting every game.
+        pub search: Option<SearchVariant>,
+        /// Solve a two player deal by alpha-beta search, with the transposition table if one
+        /// is given.
+        pub alpha_beta: bool,
+        pub weighting: Weighting,
+        pub scoring: PenaltyScoring,
+    }
+
+    #[derive(Debug, Error)]
+    pub enum CliError {
+        #[error("Missing value for {0}")]
+        MissingValue(String),
+
+        #[error("Invalid value '{1}' for {0}")]
+        InvalidValue(String, String),
+
+        #[error("Invalid card for {0}: {1}")]
+        InvalidCard(String, CardParseError),
+
+        #[error("Unknown argument '{0}'")]
+        UnknownArgument(String),
+
+        #[error("Invalid rules: {0}")]
+        InvalidRules(#[from] RuleSetError),
+    }
+
+    impl Default for Options {
+        fn default() -> Options {
+            Options {
+                players: 4,
+                dealer: None,
+                rules: RuleSet::default(),
+                match_target: None,
+                table_megabytes: None,
+                threads: None,
+                search: None,
+                alpha_beta: false,
+                weighting: Weighting::Leaves,
+                scoring: PenaltyScoring::PerCard,
+            }
+        }
+    }
+
+    /// Parses `--flag value` pairs. Seats are given counting from 1, as they are printed.
+    pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, CliError> {
+        let mut options = Options::default();
+        let mut opening = options.rules.opening.clone();
+        let mut rules = RuleSet::builder();
+        let mut optional_knock = false;
+        let mut stalemate = StalemateResolution::Draw;
+        let mut decks: u8 = 1;
+        let mut duplicate_rows = DuplicateRows::Separate;
+        let mut wilds = WildRule {
+            jokers: 0,
+            wild_number: None,
+            replacement: WildReplacement::Reclaimed,
+        };
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let value = match args.next() {
+                Some(value) => value,
+                None => return Err(CliError::MissingValue(flag)),
+            };
+            match flag.as_str() {
+                "--players" => options.players = parse_number(&flag, &value)?,
+                "--dealer" => options.dealer = Some(parse_seat(&flag, &value)?),
+                "--match" => options.match_target = Some(parse_bounded(&flag, &value)?),
+                "--table-mb" => options.table_megabytes = Some(parse_number(&flag, &value)?),
+                "--threads" => options.threads = Some(parse_number(&flag, &value)?),
+                "--search" if value == "alpha-beta" => options.alpha_beta = true,
+                "--search" => {
+                    options.search = Some(match value.as_str() {
+                        "max-n" => SearchVariant::MaxN,
+                        "paranoid" => SearchVariant::Paranoid,
+                        "best-reply" => SearchVariant::BestReply,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    })
+                }
+                "--weighting" => {
+                    options.weighting = match value.as_str() {
+                        "leaves" => Weighting::Leaves,
+                        "uniform" => Weighting::UniformPlay,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    }
+                }
+                "--scoring" => {
+                    options.scoring = match value.as_str() {
+                        "cards" => PenaltyScoring::PerCard,
+                        "pips" => PenaltyScoring::PipValue,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    }
+                }
+                "--open" => {
+                    opening.opening_card = match value.as_str() {
+                        "any" => OpeningCard::Any,
+                        _ => OpeningCard::Card(
+                            value
+                                .parse::<Card>()
+                                .map_err(|e| CliError::InvalidCard(flag.clone(), e))?,
+                        ),
+                    }
+                }
+                "--first" => {
+                    opening.first_player = match value.as_str() {
+                        "left" => FirstPlayer::LeftOfDealer,
+                        "holder" => FirstPlayer::HolderOfOpeningCard,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    }
+                }
+                "--centre" => {
+                    rules =
+                        rules.centre(match value.as_str() {
+                            "first" => CentreRule::FirstCard,
+                            _ => CentreRule::Fixed(value.parse::<NumberEnum>().map_err(|_| {
+                                CliError::InvalidValue(flag.clone(), value.clone())
+                            })?),
+                        })
+                }
+                "--play" => {
+                    optional_knock = match value.as_str() {
+                        "forced" => false,
+                        "optional" => true,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    }
+                }
+                "--decks" => decks = parse_bounded(&flag, &value)?,
+                "--sequence" => {
+                    rules = rules.sequence(match value.as_str() {
+                        "linear" => SequenceRule::Linear,
+                        "wraparound" => SequenceRule::Wraparound,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    })
+                }
+                "--building" => {
+                    rules = rules.building(match value.as_str() {
+                        "own-centre" => BuildingRule::OwnCentre,
+                        "all-centres" => BuildingRule::AllCentres,
+                        "row-above" => BuildingRule::RowAbove,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    })
+                }
+                "--partners" => {
+                    rules = rules.partnership(match value.as_str() {
+                        "first-out" => PartnershipWin::FirstPartnerOut,
+                        "both-out" => PartnershipWin::BothPartnersOut,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    })
+                }
+                "--rows" => {
+                    duplicate_rows = match value.as_str() {
+                        "separate" => DuplicateRows::Separate,
+                        "shared" => DuplicateRows::Shared,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    }
+                }
+                "--jokers" => wilds.jokers = parse_bounded(&flag, &value)?,
+                "--wild" => {
+                    wilds.wild_number = Some(
+                        value
+                            .parse::<NumberEnum>()
+                            .map_err(|_| CliError::InvalidValue(flag.clone(), value.clone()))?,
+                    )
+                }
+                "--reclaim" => {
+                    wilds.replacement = match value.as_str() {
+                        "yes" => WildReplacement::Reclaimed,
+                        "no" => WildReplacement::LeftOnTable,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    }
+                }
+                "--fan-tan" => {
+                    let amounts: Vec<u32> = value
+                        .split(',')
+                        .map(|amount| amount.trim().parse::<u32>())
+                        .collect::<Result<Vec<u32>, _>>()
+                        .map_err(|_| CliError::InvalidValue(flag.clone(), value.clone()))?;
+                    if amounts.len() != 3 {
+                        return Err(CliError::InvalidValue(flag, value));
+                    }
+                    rules = rules.chips(ChipRule {
+                        ante: amounts[0],
+                        knock_fee: amounts[1],
+                        per_card: amounts[2],
+                    })
+                }
+                "--stalemate" => {
+                    stalemate = match value.as_str() {
+                        "draw" => StalemateResolution::Draw,
+                        "force" => StalemateResolution::ForcedPlay,
+                        "fewest-cards" => StalemateResolution::FewestCards,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    }
+                }
+                "--deck" => {
+                    rules = rules.deck(match value.as_str() {
+                        "standard" => DeckSpec::Standard,
+                        "piquet" => DeckSpec::Piquet,
+                        "36" => DeckSpec::ThirtySix,
+                        "spanish" => DeckSpec::Spanish,
+                        _ => DeckSpec::Custom(
+                            value
+                                .split(',')
+                                .map(|card| card.parse::<Card>())
+                                .collect::<Result<Vec<Card>, CardParseError>>()
+                                .map_err(|e| CliError::InvalidCard(flag.clone(), e))?,
+                        ),
+                    })
+                }
+                "--dealing" => {
+                    rules = rules.dealing(match value.as_str() {
+                        "round-robin" => DealingScheme::RoundRobin,
+                        "dealers-left" => DealingScheme::FromDealersLeft,
+                        "widow" => DealingScheme::Widow,
+                        "extras-to-leader" => DealingScheme::ExtrasToLeader,
+                        _ => match value.strip_prefix("packets:") {
+                            Some(size) => DealingScheme::Packets(parse_bounded(&flag, size)?),
+                            None => return Err(CliError::InvalidValue(flag, value)),
+                        },
+                    })
+                }
+                "--end" => {
+                    rules = rules.end(match value.as_str() {
+                        "first-out" => EndCondition::FirstOut,
+                        "full-order" => EndCondition::FullOrder,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    })
+                }
+                "--hand-size" => rules = rules.hand_size(parse_bounded(&flag, &value)?),
+                "--runs" => {
+                    rules = rules.runs(match value.as_str() {
+                        "yes" => true,
+                        "no" => false,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    })
+                }
+                "--bonus-turn" => {
+                    rules = rules.bonus_turn(match value.as_str() {
+                        "completed" => BonusTurn::CompletedDirection,
+                        "centre" => BonusTurn::Centre,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    })
+                }
+                "--knock-penalty" => {
+                    rules = rules.knock_penalty(match value.as_str() {
+                        "take" => KnockPenalty::TakeFromLast,
+                        "give" => KnockPenalty::GiveToNext,
+                        _ => return Err(CliError::InvalidValue(flag, value)),
+                    })
+                }
+                _ => return Err(CliError::UnknownArgument(flag)),
             }
         }
+        let play = match optional_knock {
+            false => PlayRule::Forced,
+            true => PlayRule::OptionalKnock(stalemate),
+        };
+        if wilds.jokers > 0 || wilds.wild_number.is_some() {
+            rules = rules.wilds(wilds);
+        }
+        options.rules = rules
+            .opening(opening)
+            .play(play)
+            .decks(decks, duplicate_rows)
+            .build()?;
+        Ok(options)
     }
-}
-
-mod game_board {
-
-    use crate::card_and_enums::{Card, SuitEnum};
-    use crate::stack::{Stack, StackError};
-    use thiserror::Error;
 
-    #[derive(Debug, Clone)]
-    pub struct GameBoard {
-        spade_stack: Stack,
-        club_stack: Stack,
-        heart_stack: Stack,
-        diamond_stack: Stack,
+    fn parse_number(flag: &str, value: &str) -> Result<usize, CliError> {
+        value
+            .parse::<usize>()
+            .map_err(|_| CliError::InvalidValue(flag.to_string(), value.to_string()))
     }
 
-    #[derive(Debug, Error)]
-    pub enum GameBoardError {
-        #[error("'{0}' error in {1} stack")]
-        StackError(StackError, String),
+    /// Parses a number that has to fit in a smaller type than `usize`.
+    fn parse_bounded<T: TryFrom<usize>>(flag: &str, value: &str) -> Result<T, CliError> {
+        T::try_from(parse_number(flag, value)?)
+            .map_err(|_| CliError::InvalidValue(flag.to_string(), value.to_string()))
     }
 
-    impl GameBoard {
-        pub fn new() -> GameBoard {
-            return GameBoard {
-                spade_stack: Stack::new(SuitEnum::Spade),
-                club_stack: Stack::new(SuitEnum::Club),
-                heart_stack: Stack::new(SuitEnum::Heart),
-                diamond_stack: Stack::new(SuitEnum::Diamond),
-            };
-        }
-
-        #[cfg(test)]
-        pub fn from(stacks: Vec<Stack>) -> Result<GameBoard, GameBoardError> {
-            let mut output = GameBoard::new();
-            for st in stacks {
-                match st.suit {
-                    SuitEnum::Spade => output.spade_stack = st,
-                    SuitEnum::Club => output.club_stack = st,
-                    SuitEnum::Heart => output.heart_stack = st,
-                    SuitEnum::Diamond => output.diamond_stack = st,
-                }
-            }
-            match output.get_playable_cards() {
-                Ok(_) => return Ok(output),
-                Err(e) => return Err(e),
-            }
-        }
-
-        pub fn get_playable_cards(&self) -> Result<Option<Vec<Card>>, GameBoardError> {
-            let mut playable_spades: Vec<Card> = match self.spade_stack.get_playable_cards() {
-                Ok(vec_cards) => match vec_cards {
-                    Some(cards) => cards,
-                    None => vec![],
-                },
-                Err(e) => return Err(GameBoardError::StackError(e, "Spades".to_string())),
-            };
-            let mut playable_clubs: Vec<Card> = match self.club_stack.get_playable_cards() {
-                Ok(vec_cards) => match vec_cards {
-                    Some(cards) => cards,
-                    None => vec![],
-                },
-                Err(e) => return Err(GameBoardError::StackError(e, "Clubs".to_string())),
-            };
-            let mut playable_hearts: Vec<Card> = match self.heart_stack.get_playable_cards() {
-                Ok(vec_cards) => match vec_cards {
-                    Some(cards) => cards,
-                    None => vec![],
-                },
-                Err(e) => return Err(GameBoardError::StackError(e, "Hearts".to_string())),
-            };
-            let mut playable_diamonds: Vec<Card> = match self.diamond_stack.get_playable_cards() {
-                Ok(vec_cards) => match vec_cards {
-                    Some(cards) => cards,
-                    None => vec![],
-                },
-                Err(e) => return Err(GameBoardError::StackError(e, "Diamonds".to_string())),
-            };
-            let mut output: Vec<Card> = Vec::new();
-            output.append(&mut playable_spades);
-            output.append(&mut playable_clubs);
-            output.append(&mut playable_hearts);
-            output.append(&mut playable_diamonds);
-            if output.len() > 0 {
-                return Ok(Some(output));
-            }
-            return Ok(None);
-        }
-
-        pub fn play_card(&mut self, card: Card) -> Result<(), GameBoardError> {
-            match card.suit {
-                SuitEnum::Spade => self
-                    .spade_stack
-                    .play_card(card.number)
-                    .map_err(|e| GameBoardError::StackError(e, "Spades".to_string())),
-                SuitEnum::Club => self
-                    .club_stack
-                    .play_card(card.number)
-                    .map_err(|e| GameBoardError::StackError(e, "Clubs".to_string())),
-                SuitEnum::Heart => self
-                    .heart_stack
-                    .play_card(card.number)
-                    .map_err(|e| GameBoardError::StackError(e, "Hearts".to_string())),
-                SuitEnum::Diamond => self
-                    .diamond_stack
-                    .play_card(card.number)
-                    .map_err(|e| GameBoardError::StackError(e, "Diamonds".to_string())),
-            }
+    fn parse_seat(flag: &str, value: &str) -> Result<u8, CliError> {
+        match value.parse::<u8>() {
+            Ok(seat) if seat > 0 => Ok(seat - 1),
+            _ => Err(CliError::InvalidValue(flag.to_string(), value.to_string())),
         }
     }
 
     #[cfg(test)]
     mod test {
         use super::*;
-        use crate::card_and_enums::{NumberEnum, SuitEnum};
+        use crate::card_and_enums::SuitEnum;
+        use crate::rule_set::OpeningRule;
+        use crate::test_helpers::card;
+
+        fn args(line: &str) -> Vec<String> {
+            line.split_whitespace().map(|arg| arg.to_string()).collect()
+        }
 
         #[test]
-        fn initialization() {
-            let game_board = GameBoard::new();
+        fn no_arguments_gives_defaults() {
+            let options = parse_args(args("")).unwrap();
 
-            assert_eq!(game_board.club_stack.suit, SuitEnum::Club);
-            assert_eq!(game_board.spade_stack.suit, SuitEnum::Spade);
-            assert_eq!(game_board.diamond_stack.suit, SuitEnum::Diamond);
-            assert_eq!(game_board.heart_stack.suit, SuitEnum::Heart);
+            assert_eq!(options, Options::default());
         }
 
         #[test]
-        fn fresh_board_can_play_all_sevens() {
-            let game_board = GameBoard::new();
-
-            let output = game_board.get_playable_cards();
-            assert!(output.is_ok());
+        fn parses_players() {
+            let options = parse_args(args("--players 5")).unwrap();
 
-            let output = output.unwrap();
-            assert!(output.is_some());
+            assert_eq!(options.players, 5);
+        }
 
-            let output = output.unwrap();
+        #[test]
+        fn parses_opening_rule() {
+            let options =
+                parse_args(args("--players 5 --dealer 2 --open 7D --first holder")).unwrap();
 
-            assert_eq!(output.len(), 4);
-            assert!(output.contains(&Card {
-                suit: SuitEnum::Club,
-                number: NumberEnum::Seven
-            }));
-            assert!(output.contains(&Card {
-                suit: SuitEnum::Spade,
-                number: NumberEnum::Seven
-            }));
-            assert!(output.contains(&Card {
-                suit: SuitEnum::Heart,
-                number: NumberEnum::Seven
-            }));
-            assert!(output.contains(&Card {
-                suit: SuitEnum::Diamond,
-                number: NumberEnum::Seven
-            }));
+            assert_eq!(options.players, 5);
+            assert_eq!(options.dealer, Some(1));
+            assert_eq!(
+                options.rules.opening,
+                OpeningRule::new(
+                    OpeningCard::Card(card(SuitEnum::Diamond, NumberEnum::Seven)),
+                    FirstPlayer::HolderOfOpeningCard
+                )
+            );
         }
 
         #[test]
-        fn played_seven_plays_eight_and_six() {
-            let mut game_board = GameBoard::new();
+        fn parses_centre() {
+            let options = parse_args(args("--centre 8 --open 8H")).unwrap();
 
-            game_board
-                .club_stack
-                .play_card(NumberEnum::Seven)
-                .expect("Failed to play seven");
+            assert_eq!(options.rules.centre, CentreRule::Fixed(NumberEnum::Eight));
+        }
 
-            let output = game_board.get_playable_cards();
-            assert!(output.is_ok());
+        #[test]
+        fn parses_first_card_centre() {
+            let options = parse_args(args("--centre first")).unwrap();
 
-            let output = output.unwrap();
-            assert!(output.is_some());
+            assert_eq!(options.rules.centre, CentreRule::FirstCard);
+        }
 
-            let output = output.unwrap();
+        #[test]
+        fn parses_building_rule() {
+            let options = parse_args(args("--building row-above")).unwrap();
 
-            assert_eq!(output.len(), 5);
-            assert!(output.contains(&Card {
-                suit: SuitEnum::Club,
-                number: NumberEnum::Eight
-            }));
-            assert!(output.contains(&Card {
-                suit: SuitEnum::Club,
-                number: NumberEnum::Six
-            }));
-            assert!(output.contains(&Card {
-                suit: SuitEnum::Spade,
-                number: NumberEnum::Seven
-            }));
-            assert!(output.contains(&Card {
-                suit: SuitEnum::Heart,
-                number: NumberEnum::Seven
-            }));
-            assert!(output.contains(&Card {
-                suit: SuitEnum::Diamond,
-                number: NumberEnum::Seven
-            }));
+            assert_eq!(options.rules.building, BuildingRule::RowAbove);
+            assert!(parse_args(args("--building never")).is_err());
         }
 
         #[test]
-        fn can_play_card() {
-            let mut game_board = GameBoard::new();
+        fn parses_dealing_scheme() {
+            let options = parse_args(args("--dealing packets:3")).unwrap();
+            assert_eq!(options.rules.dealing, DealingScheme::Packets(3));
 
-            match game_board.play_card(Card {
-                suit: SuitEnum::Diamond,
-                number: NumberEnum::Seven,
-            }) {
-                Ok(_) => {}
-                Err(e) => panic!("Error playing diamond 7 on empty gameboard: {e}"),
-            };
+            let options = parse_args(args("--dealing widow")).unwrap();
+            assert_eq!(options.rules.dealing, DealingScheme::Widow);
+            assert!(parse_args(args("--dealing packets:0")).is_err());
         }
 
         #[test]
-        fn cannot_play_unplayable_card() {
-            let mut game_board = GameBoard::new();
-
-            let output = game_board.play_card(Card {
-                suit: SuitEnum::Diamond,
-                number: NumberEnum::Six,
-            });
+        fn parses_partnership() {
+            let options = parse_args(args("--partners both-out")).unwrap();
 
-            assert!(output.is_err());
             assert_eq!(
-                output.unwrap_err().to_string(),
-                GameBoardError::StackError(
-                    StackError::UnplayableCardNumber,
-                    "Diamonds".to_string()
-                )
-                .to_string()
-            )
+                options.rules.partnership,
+                Some(PartnershipWin::BothPartnersOut)
+            );
         }
-    }
-}
-
-#[derive(Debug, Clone)]
-struct Player {
-    hand: Vec<Card>,
-}
 
-impl Player {
-    fn new() -> Player {
-        Player { hand: Vec::new() }
-    }
-}
+        #[test]
+        fn parses_end_condition() {
+            let options = parse_args(args("--end full-order")).unwrap();
 
-mod game_state {
+            assert_eq!(options.rules.end, EndCondition::FullOrder);
+        }
 
-    use super::{distribute_cards, generate_new_shuffle, Player};
-    use crate::card_and_enums::Card;
-    use crate::game_board::{GameBoard, GameBoardError};
-    use thiserror::Error;
+        #[test]
+        fn parses_knock_penalty() {
+            let options = parse_args(args("--knock-penalty give")).unwrap();
 
-    #[derive(Debug, Clone)]
-    pub struct GameState {
-        game_board: GameBoard,
-        pub players: Vec<Player>,
-        pub player_turn: u8,
-    }
+            assert_eq!(options.rules.knock_penalty, Some(KnockPenalty::GiveToNext));
+        }
 
-    #[derive(Debug, Error)]
-    pub enum GameStateError {
-        #[error("Players exceeded 26 player limit")]
-        TooManyPlayers,
+        #[test]
+        fn parses_hand_size() {
+            let options = parse_args(args("--hand-size 7")).unwrap();
 
-        #[error("u8 overflow error")]
-        OverflowError,
+            assert_eq!(options.rules.hand_size, Some(7));
+        }
 
-        #[error("GameBoard Error: {0}")]
-        GameBoardError(#[from] GameBoardError),
+        #[test]
+        fn parses_runs() {
+            let options = parse_args(args("--runs yes")).unwrap();
 
-        #[error("Called {0} on a state with more than one playable card")]
-        MoreThanOnePlayableCard(String),
+            assert!(options.rules.runs);
+        }
 
-        #[error("Called {0} on a state with no playable card")]
-        NoPlayableCard(String),
+        #[test]
+        fn parses_bonus_turn() {
+            let options = parse_args(args("--bonus-turn centre")).unwrap();
 
-        #[error("Called play_card_and_return on a state with only one playable card, consider using play_only_playable_card")]
-        OnlyOnePlayableCard,
+            assert_eq!(options.rules.bonus_turn, Some(BonusTurn::Centre));
+        }
 
-        #[error("Attempted to play an unplayable card in play_card_and_return")]
-        UnplayableCard,
-    }
+        #[test]
+        fn parses_sequence() {
+            let options = parse_args(args("--sequence wraparound")).unwrap();
 
-    impl GameState {
-        pub fn new(number_of_players: usize) -> Result<GameState, GameStateError> {
-            if number_of_players > 26 {
-                return Err(GameStateError::TooManyPlayers);
-            }
-            let deck = generate_new_shuffle();
-            let players = distribute_cards(number_of_players, deck);
-            return Ok(GameState {
-                game_board: GameBoard::new(),
-                players: players,
-                player_turn: 0,
-            });
+            assert_eq!(options.rules.sequence, SequenceRule::Wraparound);
         }
 
-        pub fn pass_turn(&mut self) -> Result<(), GameStateError> {
-            if self.player_turn == u8::MAX.into() {
-                return Err(GameStateError::OverflowError);
-            }
-            if self.player_turn < self.players.len() as u8 - 1 {
-                self.player_turn += 1;
-                return Ok(());
-            } else {
-                self.player_turn = 0;
-                return Ok(());
-            }
-        }
+        #[test]
+        fn parses_optional_knock() {
+            let options = parse_args(args("--stalemate fewest-cards --play optional")).unwrap();
 
-        pub fn play_only_playable_card(&mut self) -> Result<(), GameStateError> {
-            let playable = match self.game_board.get_playable_cards() {
-                Ok(result) => result,
-                Err(e) => return Err(GameStateError::GameBoardError(e)),
-            };
-            let card = match playable {
-                Some(card) => {
-                    if card.len() > 1 {
-                        return Err(GameStateError::MoreThanOnePlayableCard(
-                            "play_only_playable_card".to_string(),
-                        ));
-                    } else {
-                        card[0].to_owned()
-                    }
-                }
-                None => {
-                    return Err(GameStateError::NoPlayableCard(
-                        "play_only_playable_card".to_string(),
-                    ))
-                }
-            };
-            self.game_board
-                .play_card(card)
-                .map_err(|e| GameStateError::GameBoardError(e))?;
-            self.pass_turn()?;
-            return Ok(());
+            assert_eq!(
+                options.rules.play,
+                PlayRule::OptionalKnock(StalemateResolution::FewestCards)
+            );
         }
 
-        pub fn play_card_and_return_new(&self, card: Card) -> Result<GameState, GameStateError> {
-            let get_playable = match self.game_board.get_playable_cards() {
-                Ok(result) => result,
-                Err(e) => return Err(GameStateError::GameBoardError(e)),
-            };
-            let playable_cards = match get_playable {
-                Some(result) => match result.len() {
-                    0 => {
-                        return Err(GameStateError::NoPlayableCard(
-                            "play_card_and_return".to_string(),
-                        ))
-                    }
-                    1 => return Err(GameStateError::OnlyOnePlayableCard),
-                    _ => result,
-                },
-                None => {
-                    return Err(GameStateError::NoPlayableCard(
-                        "play_card_and_return".to_string(),
-                    ))
-                }
-            };
-            if !playable_cards.contains(&card) {
-                return Err(GameStateError::UnplayableCard);
-            } else {
-                let mut output = self.clone();
-                output
-                    .game_board
-                    .play_card(card)
-                    .map_err(|e| GameStateError::GameBoardError(e))?;
-                output.pass_turn()?;
-                return Ok(output);
-            }
+        #[test]
+        fn parses_fan_tan_chips() {
+            let options = parse_args(args("--fan-tan 2,1,3")).unwrap();
+
+            assert_eq!(
+                options.rules.chips,
+                Some(ChipRule {
+                    ante: 2,
+                    knock_fee: 1,
+                    per_card: 3,
+                })
+            );
+            assert!(parse_args(args("--fan-tan 2,1")).is_err());
+            assert!(parse_args(args("--fan-tan 2,x,1")).is_err());
         }
 
-        pub fn get_playable_cards(&self) -> Result<Option<Vec<Card>>, GameStateError> {
-            match self.game_board.get_playable_cards() {
-                Ok(cards_option) => Ok(cards_option),
-                Err(e) => Err(GameStateError::GameBoardError(e)),
-            }
-        }
-    }
+        #[test]
+        fn parses_match() {
+            let options = parse_args(args("--match 100 --scoring pips")).unwrap();
 
-    #[cfg(test)]
-    mod tests {
+            assert_eq!(options.match_target, Some(100));
+            assert_eq!(options.scoring, PenaltyScoring::PipValue);
+        }
 
-        use crate::{
-            card_and_enums::{NumberEnum, SuitEnum}, game_state, stack::Stack
-        };
+        #[test]
+        fn parses_table_size() {
+            let options = parse_args(args("--table-mb 512")).unwrap();
 
-        use super::*;
+            assert_eq!(options.table_megabytes, Some(512));
+        }
 
         #[test]
-        fn initialization_with_valid_player_count() {
-            let game_state = GameState::new(4);
+        fn parses_threads() {
+            let options = parse_args(args("--threads 8")).unwrap();
 
-            assert!(game_state.is_ok());
-            let game_state = game_state.unwrap();
-
-            assert_eq!(game_state.players.len(), 4);
-            assert_eq!(game_state.player_turn, 0 as u8);
+            assert_eq!(options.threads, Some(8));
         }
 
         #[test]
-        fn initialization_with_invalid_player_count() {
-            let game_state = GameState::new(30);
-
-            assert!(game_state.is_err());
-            let game_state = game_state.unwrap_err();
+        fn parses_weighting() {
+            let options = parse_args(args("--weighting uniform")).unwrap();
 
-            assert_eq!(
-                game_state.to_string(),
-                GameStateError::TooManyPlayers.to_string()
-            );
+            assert_eq!(options.weighting, Weighting::UniformPlay);
         }
 
         #[test]
-        fn pass_turn_advances_player_turn() {
-            let game_state = GameState::new(4);
-            assert!(game_state.is_ok());
-            let mut game_state = game_state.unwrap();
+        fn parses_search() {
+            let options = parse_args(args("--search paranoid")).unwrap();
 
-            assert_eq!(game_state.player_turn, 0 as u8);
-            let output = game_state.pass_turn();
-            assert!(output.is_ok());
-            assert_eq!(game_state.player_turn, 1 as u8);
+            assert_eq!(options.search, Some(SearchVariant::Paranoid));
         }
 
         #[test]
-        fn pass_turn_catches_overflow_err() {
-            let game_state = GameState::new(4);
-            assert!(game_state.is_ok());
-            let mut game_state = game_state.unwrap();
+        fn parses_alpha_beta() {
+            let options = parse_args(args("--search alpha-beta --table-mb 64")).unwrap();
 
-            game_state.player_turn = 255 as u8;
-            let output = game_state.pass_turn();
-            assert!(output.is_err());
-            let output = output.unwrap_err();
-            assert_eq!(
-                output.to_string(),
-                GameStateError::OverflowError.to_string()
-            );
+            assert!(options.alpha_beta);
+            assert_eq!(options.search, None);
+            assert_eq!(options.table_megabytes, Some(64));
         }
 
         #[test]
-        fn pass_turn_resets_to_0_after_last_player_turn() {
-            let game_state = GameState::new(3);
-            assert!(game_state.is_ok());
-            let mut game_state = game_state.unwrap();
-            game_state.player_turn = 2;
+        fn parses_decks() {
+            let options = parse_args(args("--rows shared --decks 2")).unwrap();
 
-            let output = game_state.pass_turn();
-            assert!(output.is_ok());
-            assert_eq!(game_state.player_turn, 0 as u8);
+            assert_eq!(options.rules.decks, 2);
+            assert_eq!(options.rules.duplicate_rows, DuplicateRows::Shared);
+            assert!(parse_args(args("--decks 0")).is_err());
         }
 
         #[test]
-        fn play_only_playable_card_errors_with_multiple_playable_cards() {
-            let mut game_state = GameState::new(3).unwrap();
-            let output = game_state.play_only_playable_card();
-            assert!(output.is_err());
-            let output = output.unwrap_err();
+        fn parses_wild_cards() {
+            let options = parse_args(args("--jokers 2 --wild 2 --reclaim no")).unwrap();
+
             assert_eq!(
-                output.to_string(),
-                GameStateError::MoreThanOnePlayableCard("play_only_playable_card".to_string())
-                    .to_string()
+                options.rules.wilds,
+                Some(WildRule {
+                    jokers: 2,
+                    wild_number: Some(NumberEnum::Two),
+                    replacement: WildReplacement::LeftOnTable,
+                })
             );
+            assert_eq!(parse_args(args("")).unwrap().rules.wilds, None);
         }
 
         #[test]
-        fn play_only_playable_card_plays_with_one_playable_card() {
-            let mut game_state = GameState::new(3).unwrap();
-            let game_board = GameBoard::from(vec![
-                Stack::get_completed_stack(SuitEnum::Club),
-                Stack::get_completed_stack(SuitEnum::Spade),
-                Stack::get_completed_stack(SuitEnum::Heart),
-            ])
-            .unwrap();
-            game_state.game_board = game_board;
-            let output = game_state.play_only_playable_card();
-            assert!(output.is_ok());
-
-            assert_eq!(game_state.player_turn, 1); // turn was passed
+        fn rejects_numbers_too_large_for_their_setting() {
+            for line in [
+                "--jokers 300",
+                "--decks 256",
+                "--hand-size 1000",
+                "--dealing packets:256",
+                "--match 99999999999",
+            ] {
+                let output = parse_args(args(line));
+
+                assert!(
+                    matches!(output, Err(CliError::InvalidValue(_, _))),
+                    "{}",
+                    line
+                );
+            }
+        }
 
-            let playables = match game_state.get_playable_cards() {
-                Ok(cards) => cards.unwrap(),
-                Err(e) => panic!("{e}"),
-            };
+        #[test]
+        fn parses_deck() {
+            let options = parse_args(args("--deck piquet")).unwrap();
+            assert_eq!(options.rules.deck, DeckSpec::Piquet);
 
-            // since we have played the seven of diamonds
-            // the next playable cards will be the six and the seven of diamonds
-            assert_eq!(playables.len(), 2);
-            assert!(playables.contains(&Card {
-                suit: SuitEnum::Diamond,
-                number: NumberEnum::Eight,
-            }));
-            assert!(playables.contains(&Card {
-                suit: SuitEnum::Diamond,
-                number: NumberEnum::Six,
-            }));
+            let options = parse_args(args("--deck 6H,7H,8H,6S,7S,8S")).unwrap();
+            assert_eq!(options.rules.deck.cards().len(), 6);
+            assert_eq!(options.rules.deck.numbers(SuitEnum::Club), vec![]);
         }
 
         #[test]
-        fn play_only_playable_card_errors_with_no_playable_card() {
-            let mut game_state = GameState::new(3).unwrap();
-            let game_board = GameBoard::from(vec![
-                Stack::get_completed_stack(SuitEnum::Club),
-                Stack::get_completed_stack(SuitEnum::Spade),
-                Stack::get_completed_stack(SuitEnum::Heart),
-                Stack::get_completed_stack(SuitEnum::Diamond),
-            ])
-            .unwrap(); // get a completed board
-            game_state.game_board = game_board; // use it
-            let output = game_state.play_only_playable_card();
-            assert!(output.is_err());
+        fn rejects_inconsistent_rules() {
+            let output = parse_args(args("--centre 8 --open 7H"));
 
-            let output = output.unwrap_err();
-            assert_eq!(
-                output.to_string(),
-                GameStateError::NoPlayableCard("play_only_playable_card".to_string()).to_string()
-            )
+            assert!(output.is_err());
         }
 
         #[test]
-        fn play_card_and_return_new_succeeds() {
-            let game_state = GameState::new(3);
-            assert!(game_state.is_ok());
-            let game_state = game_state.unwrap();
-            let output = game_state.play_card_and_return_new(Card {
-                suit: SuitEnum::Club,
-                number: NumberEnum::Seven
-            });
-            assert!(output.is_ok());
-            let output = output.unwrap();
-            
+        fn rejects_bad_arguments() {
+            assert!(parse_args(args("--players")).is_err());
+            assert!(parse_args(args("--players five")).is_err());
+            assert!(parse_args(args("--first dealer")).is_err());
+            assert!(parse_args(args("--dealer 0")).is_err());
+            assert!(parse_args(args("--open 7X")).is_err());
+            assert!(parse_args(args("--speed 3")).is_err());
+            assert!(parse_args(args("--deck tarot")).is_err());
         }
     }
 }
 
-fn generate_new_shuffle() -> Vec<Card> {
-    let mut deck = Vec::new();
+#[allow(clippy::needless_return, clippy::redundant_field_names)]
+fn generate_new_shuffle(rules: &RuleSet) -> Vec<Card> {
+    let mut deck = rules.deck_cards();
     let mut rng = thread_rng();
-    for suit in SuitEnum::iterator() {
-        for number in NumberEnum::iterator() {
-            deck.push(Card {
-                suit: suit,
-                number: number,
-            })
-        }
-    }
     deck.shuffle(&mut rng);
     return deck;
 }
 
-fn distribute_cards(number_of_players: usize, deck: Vec<Card>) -> Vec<Player> {
+/// Deals `deck` to the table, returning the players, any cards left undealt, and the stock.
+/// With a `hand_size`, only that many cards are dealt to each player and the rest of the deck
+/// becomes the stock.
+fn distribute_cards(
+    number_of_players: usize,
+    mut deck: Vec<Card>,
+    dealing: &DealingScheme,
+    dealer: u8,
+    hand_size: Option<usize>,
+) -> (Vec<Player>, Vec<Card>, Vec<Card>) {
+    let stock = match hand_size {
+        Some(size) if size * number_of_players < deck.len() => {
+            deck.split_off(size * number_of_players)
+        }
+        _ => Vec::new(),
+    };
     let mut players: Vec<Player> = Vec::new();
     for _i in 0..number_of_players {
         players.push(Player::new())
     }
-    let counter = MultiCounter::new(vec![number_of_players, 52], false);
-    for v in counter {
-        players[v[0]].hand.push(deck[v[1]].clone())
+    let left_of_dealer = (dealer as usize + 1) % number_of_players;
+    let mut undealt: Vec<Card> = Vec::new();
+    match dealing {
+        DealingScheme::RoundRobin => {
+            let counter = MultiCounter::new(vec![number_of_players, deck.len()], false);
+            for v in counter {
+                players[v[0]].hand.push(deck[v[1]].clone())
+            }
+        }
+        DealingScheme::FromDealersLeft => {
+            for (index, card) in deck.into_iter().enumerate() {
+                players[(left_of_dealer + index) % number_of_players]
+                    .hand
+                    .push(card);
+            }
+        }
+        DealingScheme::Packets(size) => {
+            for (index, card) in deck.into_iter().enumerate() {
+                let packet = index / *size as usize;
+                players[(left_of_dealer + packet) % number_of_players]
+                    .hand
+                    .push(card);
+            }
+        }
+        DealingScheme::Widow | DealingScheme::ExtrasToLeader => {
+            let dealt = deck.len() - deck.len() % number_of_players;
+            for (index, card) in deck.into_iter().enumerate() {
+                match index < dealt {
+                    true => players[(left_of_dealer + index) % number_of_players]
+                        .hand
+                        .push(card),
+                    false => undealt.push(card),
+                }
+            }
+        }
     }
-    players
+    (players, undealt, stock)
 }
 
 mod multi_counter {
@@ -1242,6 +6515,7 @@ mod multi_counter {
     }
 
     impl MultiCounter {
+        #[allow(clippy::needless_return, clippy::redundant_field_names)]
         pub fn new(
             counter_maxes: Vec<usize>,
             require_simultaneous_completion: bool,
@@ -1271,6 +6545,7 @@ mod multi_counter {
         }
 
         /// Increments the counter values and returns the new state if not complete, otherwise None.
+        #[allow(clippy::needless_return)]
         pub fn increment(&mut self) {
             let values: Vec<usize> = self
                 .get_values()
@@ -1292,6 +6567,7 @@ mod multi_counter {
     impl Iterator for MultiCounter {
         type Item = Vec<usize>;
 
+        #[allow(clippy::needless_return)]
         fn next(&mut self) -> Option<Self::Item> {
             match self.check_complete() {
                 true => return None,
@@ -1309,6 +6585,7 @@ mod multi_counter {
         use super::*;
 
         #[test]
+        #[allow(clippy::bool_assert_comparison)]
         fn test_initialization() {
             let counter = MultiCounter::new(vec![3, 5], true);
             assert_eq!(counter.counter_maxes, vec![3, 5]);
@@ -1358,6 +6635,7 @@ mod multi_counter {
         }
 
         #[test]
+        #[allow(clippy::bool_assert_comparison)]
         fn test_check_complete_when_requires_simultaneous_is_true() {
             let mut counter = MultiCounter::new(vec![2, 3], true);
             assert_eq!(counter.check_complete(), false);
@@ -1392,6 +6670,7 @@ mod multi_counter {
         }
 
         #[test]
+        #[allow(clippy::bool_assert_comparison)]
         fn test_check_complete_when_requires_simultaneous_is_false() {
             let mut counter = MultiCounter::new(vec![2, 3], false);
             assert_eq!(counter.check_complete(), false);
@@ -1464,29 +6743,112 @@ mod multi_counter {
 }
 
 enum Decision {
-    Victory(u8),
+    /// The winning seat, and each seat's net chips for the deal.
+    Victory(u8, Vec<i32>),
+    /// Every seat in finishing order, and each seat's net chips for the deal, with the first
+    /// player out taking the winnings.
+    Placings(Vec<u8>, Vec<i32>),
+    /// The player cannot play and draws from the stock. Each state follows one of the cards
+    /// they may draw, and all are equally likely.
+    Draw(Vec<GameState>),
+    Stalemate,
     NoPlayableCards(GameState),
     OnePlayableCard(GameState),
     MultiplePlayableCards(Vec<GameState>),
 }
 
+#[allow(clippy::needless_return)]
 fn assess_decision(mut game_state: GameState) -> Result<Decision, GameStateError> {
-    if game_state.players[game_state.player_turn as usize]
-        .hand
-        .is_empty()
-    {
-        return Ok(Decision::Victory(game_state.player_turn));
+    match game_state.rules.end {
+        EndCondition::FirstOut => {
+            // the game ends as soon as someone plays their last card, not when their turn
+            // comes round again, so no one else can empty their hand in the meantime
+            if let Some(winner) = game_state.winning_seat() {
+                return Ok(Decision::Victory(winner, game_state.settle(winner)));
+            }
+        }
+        EndCondition::FullOrder => {
+            if let Some(placings) = game_state.placings() {
+                let settlement = game_state.settle(placings[0]);
+                return Ok(Decision::Placings(placings, settlement));
+            }
+        }
     }
+    let may_knock = match game_state.rules.play {
+        // with forced play a full round of knocks means no one can ever play again, which
+        // happens when a wild number left on the table has taken the place of a card for good,
+        // unless a card passed under a knock penalty has reached someone who can play it.
+        // Otherwise the passed cards could go round forever.
+        PlayRule::Forced if game_state.is_stalemate() && !game_state.anyone_can_play()? => {
+            return Ok(Decision::Stalemate)
+        }
+        PlayRule::Forced => false,
+        PlayRule::OptionalKnock(resolution) => match game_state.is_stalemate() {
+            false => true,
+            true => match resolution {
+                StalemateResolution::Draw => return Ok(Decision::Stalemate),
+                StalemateResolution::FewestCards => {
+                    let winner = fewest_cards_seat(&game_state);
+                    return Ok(Decision::Victory(winner, game_state.settle(winner)));
+                }
+                // play is forced until someone plays, so a second full round of knocks means
+                // no one can
+                StalemateResolution::ForcedPlay
+                    if game_state.knock_rounds() >= 2 && !game_state.anyone_can_play()? =>
+                {
+                    return Ok(Decision::Stalemate)
+                }
+                StalemateResolution::ForcedPlay => false,
+            },
+        },
+    };
+    let wild_plays = game_state.get_wild_plays()?;
     let playable_cards = match game_state.get_playable_cards() {
         Ok(playable) => match playable {
             Some(cards) => cards,
+            None if !wild_plays.is_empty() => Vec::new(),
+            None if !game_state.stock.is_empty() => {
+                return Ok(Decision::Draw(game_state.draw_options()?));
+            }
             None => {
-                game_state.pass_turn()?;
-                return Ok(Decision::NoPlayableCards(game_state));
+                // under a knock penalty the player chooses which card to pass
+                let mut knocks = game_state.knock_options()?;
+                if knocks.len() == 1 {
+                    return Ok(Decision::NoPlayableCards(knocks.remove(0)));
+                }
+                return Ok(Decision::MultiplePlayableCards(knocks));
             }
         },
         Err(e) => return Err(e),
     };
+    let runs = game_state.get_runs()?;
+    if may_knock || !wild_plays.is_empty() || !runs.is_empty() {
+        // knocking, each placement of a wild card and each run are options alongside each
+        // playable card
+        let mut output: Vec<GameState> = Vec::new();
+        for card in playable_cards {
+            let mut next_state = game_state.clone();
+            next_state.play_card(card)?;
+            output.push(next_state);
+        }
+        for run in runs {
+            let mut next_state = game_state.clone();
+            next_state.play_run(run)?;
+            output.push(next_state);
+        }
+        for (wild, card) in wild_plays {
+            let mut next_state = game_state.clone();
+            next_state.play_wild(wild, card)?;
+            output.push(next_state);
+        }
+        if may_knock {
+            output.append(&mut game_state.knock_options()?);
+        }
+        if output.len() == 1 {
+            return Ok(Decision::OnePlayableCard(output.remove(0)));
+        }
+        return Ok(Decision::MultiplePlayableCards(output));
+    }
     if playable_cards.len() == 1 {
         game_state.play_only_playable_card()?;
         return Ok(Decision::OnePlayableCard(game_state));
@@ -1501,3 +6863,401 @@ fn assess_decision(mut game_state: GameState) -> Result<Decision, GameStateError
         }
     }
 }
+
+/// Returns the seat holding the fewest cards, the earliest seat breaking ties.
+fn fewest_cards_seat(game_state: &GameState) -> u8 {
+    let mut fewest: usize = 0;
+    for (seat, player) in game_state.players.iter().enumerate() {
+        if player.hand.len() < game_state.players[fewest].hand.len() {
+            fewest = seat;
+        }
+    }
+    fewest as u8
+}
+
+#[cfg(test)]
+mod test_helpers {
+    use crate::card_and_enums::{Card, NumberEnum, SuitEnum};
+    use crate::rule_set::DeckSpec;
+
+    pub fn card(suit: SuitEnum, number: NumberEnum) -> Card {
+        Card { suit, number }
+    }
+
+    /// Parses a comma separated list of short card names, such as `7D,10H,KS`.
+    pub fn cards(names: &str) -> Vec<Card> {
+        names.split(',').map(|name| name.parse().unwrap()).collect()
+    }
+
+    /// A custom deck holding the listed cards.
+    pub fn custom_deck(names: &str) -> DeckSpec {
+        DeckSpec::Custom(cards(names))
+    }
+}
+
+#[cfg(test)]
+mod test_assess_decision {
+    use super::*;
+    use crate::card_and_enums::{NumberEnum, SuitEnum};
+    use crate::rule_set::{
+        DuplicateRows, KnockPenalty, RuleSet, SequenceRule, WildReplacement, WildRule,
+    };
+    use crate::test_helpers::card;
+
+    /// A three player game where the first player holds only the seven and king of clubs.
+    fn game_with_play_rule(play: PlayRule) -> GameState {
+        let rules = RuleSet::builder().play(play).build().unwrap();
+        let mut game_state = GameState::new(3, rules, 2).unwrap();
+        game_state.players[0].hand = vec![
+            card(SuitEnum::Club, NumberEnum::Seven),
+            card(SuitEnum::Club, NumberEnum::King),
+        ];
+        game_state
+    }
+
+    #[test]
+    fn game_ends_when_last_card_is_played() {
+        let mut game_state = game_with_play_rule(PlayRule::Forced);
+        game_state.players[0].hand = vec![card(SuitEnum::Club, NumberEnum::Seven)];
+
+        let next_state = match assess_decision(game_state).unwrap() {
+            Decision::OnePlayableCard(state) => state,
+            _ => panic!("Expected the seven of clubs to be played"),
+        };
+        assert_eq!(next_state.player_turn, 1);
+        let decision = assess_decision(next_state).unwrap();
+
+        assert!(matches!(decision, Decision::Victory(0, _)));
+    }
+
+    #[test]
+    fn full_order_plays_on_until_one_player_holds_cards() {
+        let rules = RuleSet::builder()
+            .end(EndCondition::FullOrder)
+            .build()
+            .unwrap();
+        let mut game_state = GameState::new(3, rules, 2).unwrap();
+        game_state.players[0].hand = vec![card(SuitEnum::Club, NumberEnum::Seven)];
+        game_state.players[1].hand = vec![card(SuitEnum::Club, NumberEnum::Eight)];
+        game_state.players[2].hand = vec![card(SuitEnum::Club, NumberEnum::Nine)];
+
+        let mut decision = assess_decision(game_state).unwrap();
+        while let Decision::OnePlayableCard(state) = decision {
+            decision = assess_decision(state).unwrap();
+        }
+
+        match decision {
+            Decision::Placings(placings, _) => assert_eq!(placings, vec![0, 1, 2]),
+            _ => panic!("Expected the full finishing order"),
+        }
+    }
+
+    #[test]
+    fn wraparound_rows_branch_once_per_playable_card() {
+        let rules = RuleSet::builder()
+            .sequence(SequenceRule::Wraparound)
+            .decks(2, DuplicateRows::Shared)
+            .build()
+            .unwrap();
+        let mut game_state = GameState::new(3, rules, 2).unwrap();
+        game_state.players[0].hand = vec![
+            card(SuitEnum::Heart, NumberEnum::Seven),
+            card(SuitEnum::Club, NumberEnum::Two),
+        ];
+        game_state.players[1].hand = vec![
+            card(SuitEnum::Heart, NumberEnum::Six),
+            card(SuitEnum::Heart, NumberEnum::Seven),
+            card(SuitEnum::Heart, NumberEnum::Eight),
+            card(SuitEnum::Club, NumberEnum::Three),
+        ];
+        game_state
+            .play_card(card(SuitEnum::Heart, NumberEnum::Seven))
+            .unwrap();
+
+        let states = match assess_decision(game_state).unwrap() {
+            Decision::MultiplePlayableCards(states) => states,
+            _ => panic!("Expected a branch for each heart"),
+        };
+
+        // the second seven goes on the same row as the first, not a row of its own
+        assert_eq!(states.len(), 3);
+        for state in states.iter() {
+            assert_eq!(state.players[1].hand.len(), 3);
+        }
+    }
+
+    #[test]
+    fn knock_penalty_branches_over_the_card_taken() {
+        let rules = RuleSet::builder()
+            .knock_penalty(KnockPenalty::TakeFromLast)
+            .build()
+            .unwrap();
+        let mut game_state = GameState::new(3, rules, 2).unwrap();
+        game_state.players[0].hand = vec![
+            card(SuitEnum::Club, NumberEnum::Seven),
+            card(SuitEnum::Club, NumberEnum::Two),
+        ];
+        game_state.players[1].hand = vec![card(SuitEnum::Heart, NumberEnum::Two)];
+        game_state
+            .play_card(card(SuitEnum::Club, NumberEnum::Seven))
+            .unwrap();
+
+        assert!(matches!(
+            assess_decision(game_state.clone()).unwrap(),
+            Decision::NoPlayableCards(_)
+        ));
+        game_state.players[0]
+            .hand
+            .push(card(SuitEnum::Spade, NumberEnum::Two));
+        let states = match assess_decision(game_state).unwrap() {
+            Decision::MultiplePlayableCards(states) => states,
+            _ => panic!("Expected a choice of card to take"),
+        };
+
+        assert_eq!(states.len(), 2);
+        for state in states {
+            assert_eq!(state.players[0].hand.len(), 1);
+            assert_eq!(state.players[1].hand.len(), 2);
+            assert_eq!(state.player_turn, 2);
+        }
+    }
+
+    #[test]
+    fn card_taken_on_a_knock_can_still_be_played() {
+        let rules = RuleSet::builder()
+            .knock_penalty(KnockPenalty::TakeFromLast)
+            .build()
+            .unwrap();
+        let mut game_state = GameState::new(3, rules, 2).unwrap();
+        game_state.players[0].hand = vec![
+            card(SuitEnum::Club, NumberEnum::Seven),
+            card(SuitEnum::Club, NumberEnum::Eight),
+            card(SuitEnum::Diamond, NumberEnum::Two),
+            card(SuitEnum::Heart, NumberEnum::King),
+        ];
+        game_state.players[1].hand = vec![card(SuitEnum::Spade, NumberEnum::King)];
+        game_state.players[2].hand = vec![card(SuitEnum::Diamond, NumberEnum::King)];
+        game_state
+            .play_card(card(SuitEnum::Club, NumberEnum::Seven))
+            .unwrap();
+        game_state
+            .knock_with_transfer(card(SuitEnum::Club, NumberEnum::Eight))
+            .unwrap();
+        game_state
+            .knock_with_transfer(card(SuitEnum::Diamond, NumberEnum::Two))
+            .unwrap();
+        game_state.knock().unwrap();
+
+        // a full round of knocks, but the player to move has been passed a playable card
+        assert!(game_state.is_stalemate());
+        match assess_decision(game_state).unwrap() {
+            Decision::OnePlayableCard(state) => assert!(state.players[1].hand.len() == 1),
+            _ => panic!("Expected the eight of clubs to be played"),
+        }
+    }
+
+    #[test]
+    fn player_who_cannot_play_draws_from_the_stock() {
+        let rules = RuleSet::builder().hand_size(5).build().unwrap();
+        let mut game_state = GameState::new(3, rules, 2).unwrap();
+        assert_eq!(game_state.stock.len(), 37);
+        game_state.players[0].hand = vec![card(SuitEnum::Club, NumberEnum::Two)];
+
+        let states = match assess_decision(game_state).unwrap() {
+            Decision::Draw(states) => states,
+            _ => panic!("Expected a draw from the stock"),
+        };
+
+        assert_eq!(states.len(), 37);
+        for state in states {
+            assert_eq!(state.players[0].hand.len(), 2);
+            assert_eq!(state.stock.len(), 36);
+            assert_eq!(state.player_turn, 1);
+        }
+    }
+
+    #[test]
+    fn draws_share_the_weight_of_the_decision() {
+        let game_state = GameState::new(3, RuleSet::default(), 2).unwrap();
+        let mut branches = Vec::new();
+        let draws = vec![
+            game_state.clone(),
+            game_state.clone(),
+            game_state.clone(),
+            game_state,
+        ];
+
+        let (_, weight) =
+            process_branches(&mut branches, Decision::Draw(draws), 0.5, Weighting::Leaves).unwrap();
+
+        assert_eq!(weight, 0.125);
+        assert_eq!(branches.len(), 3);
+        assert!(branches.iter().all(|(_, weight)| *weight == 0.125));
+    }
+
+    #[test]
+    fn runs_are_offered_as_single_moves() {
+        let rules = RuleSet::builder().runs(true).build().unwrap();
+        let mut game_state = GameState::new(3, rules, 2).unwrap();
+        game_state.players[0].hand = vec![
+            card(SuitEnum::Club, NumberEnum::Seven),
+            card(SuitEnum::Club, NumberEnum::Eight),
+            card(SuitEnum::Club, NumberEnum::Six),
+            card(SuitEnum::Club, NumberEnum::Ten),
+        ];
+
+        let mut runs = game_state.get_runs().unwrap();
+        runs.sort_by_key(|run| run.len());
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].len(), 2);
+        assert_eq!(runs[1].len(), 2);
+        let states = match assess_decision(game_state).unwrap() {
+            Decision::MultiplePlayableCards(states) => states,
+            _ => panic!("Expected a choice between the seven and the runs"),
+        };
+
+        assert_eq!(states.len(), 3);
+        assert!(states.iter().any(|state| state.players[0].hand.len() == 2));
+    }
+
+    #[test]
+    fn forced_play_with_one_playable_card_plays_it() {
+        let game_state = game_with_play_rule(PlayRule::Forced);
+
+        let decision = assess_decision(game_state).unwrap();
+
+        assert!(matches!(decision, Decision::OnePlayableCard(_)));
+    }
+
+    #[test]
+    fn optional_knock_adds_knock_branch() {
+        let game_state = game_with_play_rule(PlayRule::OptionalKnock(StalemateResolution::Draw));
+
+        let states = match assess_decision(game_state).unwrap() {
+            Decision::MultiplePlayableCards(states) => states,
+            _ => panic!("Expected a choice between playing and knocking"),
+        };
+
+        assert_eq!(states.len(), 2);
+        assert_eq!(states[0].players[0].hand.len(), 1);
+        assert_eq!(states[0].consecutive_knocks, 0);
+        assert_eq!(states[1].players[0].hand.len(), 2);
+        assert_eq!(states[1].consecutive_knocks, 1);
+        assert_eq!(states[1].player_turn, 1);
+    }
+
+    #[test]
+    fn every_player_knocking_is_a_stalemate() {
+        let mut game_state =
+            game_with_play_rule(PlayRule::OptionalKnock(StalemateResolution::Draw));
+        for _ in 0..3 {
+            game_state.knock().unwrap();
+        }
+
+        let decision = assess_decision(game_state).unwrap();
+
+        assert!(matches!(decision, Decision::Stalemate));
+    }
+
+    #[test]
+    fn forced_game_where_no_one_can_play_is_a_stalemate() {
+        let mut game_state = game_with_play_rule(PlayRule::Forced);
+        // no one holds a seven, so no row can ever be opened
+        game_state.players[0].hand = vec![card(SuitEnum::Club, NumberEnum::King)];
+        game_state.players[1].hand = vec![card(SuitEnum::Heart, NumberEnum::King)];
+        game_state.players[2].hand = vec![card(SuitEnum::Spade, NumberEnum::King)];
+        for _ in 0..3 {
+            game_state.knock().unwrap();
+        }
+
+        let decision = assess_decision(game_state).unwrap();
+
+        assert!(matches!(decision, Decision::Stalemate));
+    }
+
+    #[test]
+    fn stalemate_won_by_fewest_cards() {
+        let mut game_state =
+            game_with_play_rule(PlayRule::OptionalKnock(StalemateResolution::FewestCards));
+        for _ in 0..3 {
+            game_state.knock().unwrap();
+        }
+
+        let decision = assess_decision(game_state).unwrap();
+
+        assert!(matches!(decision, Decision::Victory(0, _)));
+    }
+
+    #[test]
+    fn stalemate_forces_play() {
+        let mut game_state =
+            game_with_play_rule(PlayRule::OptionalKnock(StalemateResolution::ForcedPlay));
+        for _ in 0..3 {
+            game_state.knock().unwrap();
+        }
+
+        let decision = assess_decision(game_state).unwrap();
+
+        assert!(matches!(decision, Decision::OnePlayableCard(_)));
+    }
+
+    #[test]
+    fn forced_play_ends_in_stalemate_when_no_one_can_play() {
+        let mut game_state =
+            game_with_play_rule(PlayRule::OptionalKnock(StalemateResolution::ForcedPlay));
+        // no one holds a seven, so no row can ever be opened
+        game_state.players[0].hand = vec![card(SuitEnum::Club, NumberEnum::King)];
+        game_state.players[1].hand = vec![card(SuitEnum::Heart, NumberEnum::King)];
+        game_state.players[2].hand = vec![card(SuitEnum::Spade, NumberEnum::King)];
+
+        let mut decisions = 0;
+        let decision = loop {
+            decisions += 1;
+            match assess_decision(game_state).unwrap() {
+                Decision::NoPlayableCards(next_state) => game_state = next_state,
+                decision => break decision,
+            }
+        };
+
+        // a round of knocks brings the stalemate, and a forced round ends the game
+        assert!(matches!(decision, Decision::Stalemate));
+        assert_eq!(decisions, 7);
+    }
+
+    #[test]
+    fn wild_number_branches_over_its_placements() {
+        let rules = RuleSet::builder()
+            .wilds(WildRule {
+                jokers: 0,
+                wild_number: Some(NumberEnum::Two),
+                replacement: WildReplacement::Reclaimed,
+            })
+            .build()
+            .unwrap();
+        let mut game_state = GameState::new(3, rules, 2).unwrap();
+        game_state.players[0].hand = vec![
+            card(SuitEnum::Club, NumberEnum::Seven),
+            card(SuitEnum::Heart, NumberEnum::Two),
+            card(SuitEnum::Club, NumberEnum::King),
+        ];
+        game_state
+            .play_card(card(SuitEnum::Club, NumberEnum::Seven))
+            .unwrap();
+        game_state.player_turn = 0;
+
+        let decision = assess_decision(game_state).unwrap();
+
+        match decision {
+            Decision::MultiplePlayableCards(states) => {
+                assert_eq!(states.len(), 2);
+                for state in states {
+                    assert_eq!(
+                        state.players[0].hand,
+                        vec![card(SuitEnum::Club, NumberEnum::King)]
+                    );
+                }
+            }
+            _ => panic!("Expected the two of hearts to be played as the six or eight of clubs"),
+        }
+    }
+}